@@ -7,6 +7,16 @@ import { Label } from "@/components/ui/label";
 import Countdown from "react-countdown";
 import { useParams } from "react-router-dom";
 
+type PlayerStats = {
+  damage_dealt: number;
+  damage_taken: number;
+  kills: number;
+  shots_fired: number;
+  shots_hit: number;
+  accuracy: number;
+  survival_ticks: number;
+};
+
 type Player = {
   id: string;
   name: string;
@@ -15,6 +25,14 @@ type Player = {
   rotation: number;
   color: string;
   health: number;
+  stats: PlayerStats;
+  shield: number;
+  speed_boost_ticks: number;
+  damage_boost_ticks: number;
+  ammo: number;
+  reload_ticks: number;
+  shot_cooldown_ticks: number;
+  weapon: WeaponType;
   entity_type: "PLAYER";
 };
 
@@ -26,17 +44,125 @@ type Projectile = {
   x: number;
   y: number;
   direction: number;
+  projectile_type: ProjectileType;
+  damage: number;
+  remaining_range: number | null;
+  remaining_ticks: number | null;
   entity_type: "PROJECTILE";
 };
 
+type ProjectileType = "BULLET" | "PELLET" | "ROCKET" | "LASER";
+
+type WeaponType = "RIFLE" | "SHOTGUN" | "ROCKET" | "LASER";
+
+const PROJECTILE_COLORS: Record<ProjectileType, string> = {
+  BULLET: "black",
+  PELLET: "rgba(120,53,15,1)",
+  ROCKET: "rgba(234,88,12,1)",
+  LASER: "rgba(220,38,38,1)",
+};
+
+type Obstacle = {
+  x: number;
+  y: number;
+};
+
+type ZoneBounds = {
+  center_x: number;
+  center_y: number;
+  radius_x: number;
+  radius_y: number;
+};
+
+type Zone = {
+  shape: "CIRCLE" | "RECTANGLE";
+  current: ZoneBounds;
+  next: ZoneBounds;
+  ticks_until_shrink: number | null;
+};
+
+function renderZoneBounds(
+  shape: Zone["shape"],
+  bounds: ZoneBounds,
+  stroke: string,
+  dashed: boolean,
+  height: number,
+) {
+  const strokeDasharray = dashed ? "4 2" : undefined;
+
+  if (shape === "CIRCLE") {
+    return (
+      <circle
+        cx={bounds.center_x * 10 + 5}
+        cy={height - bounds.center_y * 10 - 5} // Invert Y axis
+        r={bounds.radius_x * 10 + 5}
+        fill="none"
+        stroke={stroke}
+        strokeWidth="2"
+        strokeDasharray={strokeDasharray}
+      />
+    );
+  }
+
+  return (
+    <rect
+      x={(bounds.center_x - bounds.radius_x) * 10}
+      y={height - (bounds.center_y + bounds.radius_y) * 10 - 10} // Invert Y axis
+      width={(bounds.radius_x * 2 + 1) * 10}
+      height={(bounds.radius_y * 2 + 1) * 10}
+      fill="none"
+      stroke={stroke}
+      strokeWidth="2"
+      strokeDasharray={strokeDasharray}
+    />
+  );
+}
+
+type PickupType = "HEALTH" | "SHIELD" | "SPEED_BOOST" | "DAMAGE_BOOST";
+
+type Pickup = {
+  id: string;
+  pickup_type: PickupType;
+  x: number;
+  y: number;
+  entity_type: "PICKUP";
+};
+
+const PICKUP_STYLES: Record<PickupType, { label: string; color: string }> = {
+  HEALTH: { label: "+", color: "rgba(34,197,94,1)" },
+  SHIELD: { label: "S", color: "rgba(59,130,246,1)" },
+  SPEED_BOOST: { label: "»", color: "rgba(234,179,8,1)" },
+  DAMAGE_BOOST: { label: "!", color: "rgba(239,68,68,1)" },
+};
+
 type GameState = {
   tick: string;
   tick_length_milli_seconds: number;
+  field_size_x: number;
+  field_size_y: number;
   players: Player[];
-  entities: Projectile[];
+  entities: (Projectile | Pickup)[];
+  obstacles: Obstacle[];
+  zone: Zone | null;
   spectators: number;
 };
 
+type Standing = {
+  placement: number;
+  player_id: string;
+  name: string;
+  health: number;
+  eliminated_in_round: number | null;
+  stats: PlayerStats;
+};
+
+type GameOver = {
+  reason: "LAST_PLAYER_STANDING" | "MAX_ROUNDS_REACHED" | "STOPPED_BY_ADMIN";
+  winner_id: string | null;
+  round: number;
+  standings: Standing[];
+};
+
 const calculateTrajectoryEndpoint = (
   x: number,
   y: number,
@@ -52,6 +178,7 @@ const calculateTrajectoryEndpoint = (
 export default function Game() {
   const { lobbyId } = useParams();
   const [gameState, setGameState] = useState<GameState | null>(null);
+  const [gameOver, setGameOver] = useState<GameOver | null>(null);
   const [showGrid, setShowGrid] = useState(true);
   const [showTrajectory, setShowTrajectory] = useState(true);
   const wsRef = useRef<WebSocket | null>(null);
@@ -76,9 +203,15 @@ export default function Game() {
       };
 
       wsRef.current.onmessage = (event) => {
-        const newGameState: GameState = JSON.parse(event.data);
+        const message: GameState | GameOver = JSON.parse(event.data);
+
+        if ("reason" in message) {
+          setGameOver(message);
+          return;
+        }
 
-        setGameState(newGameState);
+        setGameOver(null);
+        setGameState(message);
       };
 
       wsRef.current.onerror = (error) => {
@@ -96,28 +229,40 @@ export default function Game() {
   }
 
   const players = gameState.players;
+  const projectiles = gameState.entities.filter(
+    (entity): entity is Projectile => entity.entity_type === "PROJECTILE",
+  );
+  const pickups = gameState.entities.filter(
+    (entity): entity is Pickup => entity.entity_type === "PICKUP",
+  );
+  const width = gameState.field_size_x * 10;
+  const height = gameState.field_size_y * 10;
 
   const renderGrid = () => {
     if (!showGrid) return null;
 
     const gridLines = [];
-    for (let i = 1; i < 31; i++) {
+    for (let i = 1; i <= gameState.field_size_x; i++) {
       gridLines.push(
         <line
           key={`v${i}`}
           x1={i * 10}
           y1="0"
           x2={i * 10}
-          y2="300"
+          y2={height}
           stroke="rgba(0,0,0,0.2)"
           strokeWidth="0.5"
         />,
+      );
+    }
+    for (let i = 1; i <= gameState.field_size_y; i++) {
+      gridLines.push(
         <line
           key={`h${i}`}
           x1="0"
-          y1={300 - i * 10}
-          x2="300"
-          y2={300 - i * 10}
+          y1={height - i * 10}
+          x2={width}
+          y2={height - i * 10}
           stroke="rgba(0,0,0,0.2)"
           strokeWidth="0.5"
         />,
@@ -130,6 +275,41 @@ export default function Game() {
     <div className="w-full max-w-6xl mx-auto p-4">
       <h2 className="text-2xl font-bold mb-4">Game Visualization</h2>
       <p className="mb-4">Spectators: {gameState.spectators}</p>
+      {gameOver && (
+        <Card className="mb-4">
+          <CardHeader>
+            <CardTitle>
+              Game Over: {gameOver.reason} (Round {gameOver.round})
+            </CardTitle>
+          </CardHeader>
+          <CardContent>
+            <table className="w-full text-left">
+              <thead>
+                <tr>
+                  <th>#</th>
+                  <th>Player</th>
+                  <th>Health</th>
+                  <th>Kills</th>
+                  <th>Damage Dealt</th>
+                  <th>Accuracy</th>
+                </tr>
+              </thead>
+              <tbody>
+                {gameOver.standings.map((standing) => (
+                  <tr key={standing.player_id}>
+                    <td>{standing.placement}</td>
+                    <td>{standing.name}</td>
+                    <td>{standing.health}</td>
+                    <td>{standing.stats.kills}</td>
+                    <td>{standing.stats.damage_dealt}</td>
+                    <td>{(standing.stats.accuracy * 100).toFixed(0)}%</td>
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+          </CardContent>
+        </Card>
+      )}
       <div className="flex flex-col md:flex-row gap-4">
         <Card className="w-full md:w-1/2">
           <CardContent className="p-4">
@@ -156,10 +336,62 @@ export default function Game() {
             <svg
               width="100%"
               height="100%"
-              viewBox="0 0 300 300"
+              viewBox={`0 0 ${width} ${height}`}
               className="border border-gray-300"
             >
               {renderGrid()}
+              {gameState.zone && (
+                <g>
+                  {renderZoneBounds(
+                    gameState.zone.shape,
+                    gameState.zone.current,
+                    "rgba(37,99,235,1)",
+                    false,
+                    height,
+                  )}
+                  {gameState.zone.ticks_until_shrink !== null &&
+                    renderZoneBounds(
+                      gameState.zone.shape,
+                      gameState.zone.next,
+                      "rgba(37,99,235,0.5)",
+                      true,
+                      height,
+                    )}
+                </g>
+              )}
+              {gameState.obstacles.map((obstacle) => (
+                <rect
+                  key={`obstacle-${obstacle.x}-${obstacle.y}`}
+                  x={obstacle.x * 10}
+                  y={height - obstacle.y * 10 - 10} // Invert Y axis
+                  width="10"
+                  height="10"
+                  fill="rgba(75,85,99,1)"
+                />
+              ))}
+              {pickups.map((pickup) => (
+                <g
+                  key={pickup.id}
+                  transform={`translate(${pickup.x * 10 + 5}, ${height - pickup.y * 10 - 5})`} // Invert Y axis
+                >
+                  <rect
+                    x="-4"
+                    y="-4"
+                    width="8"
+                    height="8"
+                    transform="rotate(45)"
+                    fill={PICKUP_STYLES[pickup.pickup_type].color}
+                  />
+                  <text
+                    y="2.5"
+                    textAnchor="middle"
+                    fill="white"
+                    fontSize="7"
+                  >
+                    {PICKUP_STYLES[pickup.pickup_type].label}
+                  </text>
+                </g>
+              ))}
               <AnimatePresence>
                 {gameState.players.map((entity) => {
                   return (
@@ -169,7 +401,7 @@ export default function Game() {
                       animate={{
                         opacity: 1,
                         x: entity.x * 10 + 5,
-                        y: 300 - entity.y * 10 - 5, // Invert Y axis
+                        y: height - entity.y * 10 - 5, // Invert Y axis
                       }}
                       exit={{ opacity: 0 }}
                       transition={{ type: "tween", duration: 0.05 }}
@@ -195,7 +427,7 @@ export default function Game() {
                     </motion.g>
                   );
                 })}
-                {gameState.entities.map((entity) => {
+                {projectiles.map((entity) => {
                   const { endX: nextTurnX, endY: nextTurnY } =
                     calculateTrajectoryEndpoint(
                       entity.x,
@@ -217,9 +449,9 @@ export default function Game() {
                         <>
                           <motion.line
                             x1={entity.x * 10 + 5}
-                            y1={300 - entity.y * 10 - 5} // Invert Y axis
+                            y1={height - entity.y * 10 - 5} // Invert Y axis
                             x2={nextTurnX * 10 + 5}
-                            y2={300 - nextTurnY * 10 - 5} // Invert Y axis
+                            y2={height - nextTurnY * 10 - 5} // Invert Y axis
                             key={`${entity.id}-next-trajectory`}
                             stroke="rgba(0,0,0,1)"
                             strokeWidth="1"
@@ -229,9 +461,9 @@ export default function Game() {
                           />
                           <motion.line
                             x1={entity.x * 10 + 5}
-                            y1={300 - entity.y * 10 - 5} // Invert Y axis
+                            y1={height - entity.y * 10 - 5} // Invert Y axis
                             x2={endX * 10 + 5}
-                            y2={300 - endY * 10 - 5} // Invert Y axis
+                            y2={height - endY * 10 - 5} // Invert Y axis
                             key={`${entity.id}-trajectory`}
                             stroke="rgba(0,0,0,0.3)"
                             strokeWidth="1"
@@ -241,18 +473,28 @@ export default function Game() {
                           />
                         </>
                       )}
+                      {entity.projectile_type === "LASER" && (
+                        <line
+                          x1={entity.previous_x * 10 + 5}
+                          y1={height - entity.previous_y * 10 - 5} // Invert Y axis
+                          x2={entity.x * 10 + 5}
+                          y2={height - entity.y * 10 - 5} // Invert Y axis
+                          stroke={PROJECTILE_COLORS.LASER}
+                          strokeWidth="2"
+                        />
+                      )}
                       <motion.path
                         d="M-5,-2 L5,0 L-5,2 Z"
-                        fill="black"
+                        fill={PROJECTILE_COLORS[entity.projectile_type]}
                         initial={{
                           opacity: 0,
                           x: entity.previous_x * 10 + 5,
-                          y: 300 - entity.previous_y * 10 - 5,
+                          y: height - entity.previous_y * 10 - 5,
                         }}
                         animate={{
                           opacity: 1,
                           x: entity.x * 10 + 5,
-                          y: 300 - entity.y * 10 - 5, // Invert Y axis
+                          y: height - entity.y * 10 - 5, // Invert Y axis
                           rotate: entity.direction - 90, // to offset default svg rotation
                         }}
                         exit={{ opacity: 0 }}
@@ -307,6 +549,36 @@ export default function Game() {
                     <span>{player.health}%</span>
                   </div>
                   <Progress value={player.health} className="w-full" />
+                  {player.shield > 0 && (
+                    <div className="flex justify-between">
+                      <span>Shield:</span>
+                      <span>{player.shield}</span>
+                    </div>
+                  )}
+                  {player.speed_boost_ticks > 0 && (
+                    <div className="flex justify-between">
+                      <span>Speed boost:</span>
+                      <span>{player.speed_boost_ticks} ticks</span>
+                    </div>
+                  )}
+                  {player.damage_boost_ticks > 0 && (
+                    <div className="flex justify-between">
+                      <span>Damage boost:</span>
+                      <span>{player.damage_boost_ticks} ticks</span>
+                    </div>
+                  )}
+                  <div className="flex justify-between">
+                    <span>Weapon:</span>
+                    <span>{player.weapon}</span>
+                  </div>
+                  <div className="flex justify-between">
+                    <span>Ammo:</span>
+                    <span>
+                      {player.reload_ticks > 0
+                        ? `Reloading (${player.reload_ticks} ticks)`
+                        : player.ammo}
+                    </span>
+                  </div>
                   <div className="flex justify-between">
                     <span>Position:</span>
                     <span>
@@ -317,6 +589,14 @@ export default function Game() {
                     <span>Rotation:</span>
                     <span>{player.rotation.toFixed(2)}°</span>
                   </div>
+                  <div className="flex justify-between">
+                    <span>Kills:</span>
+                    <span>{player.stats.kills}</span>
+                  </div>
+                  <div className="flex justify-between">
+                    <span>Accuracy:</span>
+                    <span>{(player.stats.accuracy * 100).toFixed(0)}%</span>
+                  </div>
                 </div>
               </CardContent>
             </Card>
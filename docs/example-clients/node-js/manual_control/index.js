@@ -107,6 +107,55 @@ process.stdin.on("data", (key) => {
           },
         });
         break;
+      case "r":
+        sendMessage({
+          socketConnection,
+          tick: currentTick,
+          payload: {
+            action: "RELOAD",
+          },
+        });
+        break;
+      case "1":
+        sendMessage({
+          socketConnection,
+          tick: currentTick,
+          payload: {
+            action: "SWITCH_WEAPON",
+            weapon: "RIFLE",
+          },
+        });
+        break;
+      case "2":
+        sendMessage({
+          socketConnection,
+          tick: currentTick,
+          payload: {
+            action: "SWITCH_WEAPON",
+            weapon: "SHOTGUN",
+          },
+        });
+        break;
+      case "3":
+        sendMessage({
+          socketConnection,
+          tick: currentTick,
+          payload: {
+            action: "SWITCH_WEAPON",
+            weapon: "ROCKET",
+          },
+        });
+        break;
+      case "4":
+        sendMessage({
+          socketConnection,
+          tick: currentTick,
+          payload: {
+            action: "SWITCH_WEAPON",
+            weapon: "LASER",
+          },
+        });
+        break;
       default:
         console.log(`\nKey ${key} triggers no special action.`);
     }
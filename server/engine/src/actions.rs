@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::WeaponType;
+
+#[allow(non_camel_case_types)]
+#[derive(Serialize, Deserialize, Clone)]
+pub enum ClientAction {
+    SHOOT,
+    RELOAD,
+    TURN,
+    UP,
+    DOWN,
+    LEFT,
+    RIGHT,
+    SWITCH_WEAPON,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PlayerAction {
+    pub action: ClientAction,
+    pub degrees: Option<i32>,
+    #[serde(default)]
+    pub weapon: Option<WeaponType>,
+}
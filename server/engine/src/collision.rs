@@ -0,0 +1,67 @@
+/// Half the side length of the square, which a player or obstacle occupies around the center of
+/// its field.
+pub const FIELD_HALF_SIZE: f64 = 0.5;
+/// Overlaps shorter than this share of a tick are treated as touching, so projectiles passing
+/// exactly along the border or through the corner of a field do not hit it despite rounding
+/// errors.
+const MIN_OVERLAP: f64 = 1e-9;
+
+/// Time within a tick (0.0 - 1.0), at which a projectile moving from `projectile_start` to
+/// `projectile_end` enters the field of a player moving from `field_start` to `field_end`. Both
+/// move with constant speed during the tick, a resting player or obstacle has the same start and
+/// end. `None` if the projectile misses the field or only touches its border.
+///
+/// The projectile is tested against the field in the frame of reference of the field, where the
+/// field rests and the projectile moves along a single segment (slab test).
+pub fn get_hit_time(
+    projectile_start: (f64, f64),
+    projectile_end: (f64, f64),
+    field_start: (f64, f64),
+    field_end: (f64, f64),
+) -> Option<f64> {
+    let origin = (
+        projectile_start.0 - field_start.0,
+        projectile_start.1 - field_start.1,
+    );
+    let direction = (
+        (projectile_end.0 - projectile_start.0) - (field_end.0 - field_start.0),
+        (projectile_end.1 - projectile_start.1) - (field_end.1 - field_start.1),
+    );
+
+    let (enter_x, exit_x) = get_slab_interval(origin.0, direction.0)?;
+    let (enter_y, exit_y) = get_slab_interval(origin.1, direction.1)?;
+
+    let enter = enter_x.max(enter_y).max(0.0);
+    let exit = exit_x.min(exit_y).min(1.0);
+
+    if exit - enter <= MIN_OVERLAP {
+        return None;
+    }
+
+    return Some(enter);
+}
+
+/// Position at the given time (0.0 - 1.0) of something moving from `start` to `end` during a tick.
+pub fn interpolate(start: (f64, f64), end: (f64, f64), time: f64) -> (f64, f64) {
+    return (
+        start.0 + (end.0 - start.0) * time,
+        start.1 + (end.1 - start.1) * time,
+    );
+}
+
+/// Times between which a point at `origin` moving by `direction` per tick is strictly between
+/// the borders of a field along one axis.
+fn get_slab_interval(origin: f64, direction: f64) -> Option<(f64, f64)> {
+    if direction == 0.0 {
+        if origin.abs() < FIELD_HALF_SIZE {
+            return Some((f64::NEG_INFINITY, f64::INFINITY));
+        }
+
+        return None;
+    }
+
+    let first = (-FIELD_HALF_SIZE - origin) / direction;
+    let second = (FIELD_HALF_SIZE - origin) / direction;
+
+    return Some((first.min(second), first.max(second)));
+}
@@ -0,0 +1,350 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+
+use crate::formations;
+use crate::maps::{self, GameMap};
+use crate::models::{Obstacle, PickupLocation, Spawn, ZoneShape};
+
+/// Schedule of the shrinking safe zone. The zone starts covering the whole field and shrinks
+/// around the center of the field.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ZoneConfig {
+    pub shape: ZoneShape,
+    /// Number of ticks until the zone shrinks the first time.
+    pub shrink_start_ticks: i32,
+    /// Number of ticks between two shrinks.
+    pub shrink_interval_ticks: i32,
+    /// Size of the next zone relative to the current zone (0.0 - 1.0).
+    pub shrink_factor: f64,
+    /// Radius the zone does not shrink below.
+    pub min_radius: f64,
+    /// Health players outside of the zone lose every tick. The damage is not absorbed by shields.
+    pub damage_per_tick: i16,
+}
+
+impl Default for ZoneConfig {
+    fn default() -> Self {
+        ZoneConfig {
+            shape: ZoneShape::CIRCLE,
+            shrink_start_ticks: 100,
+            shrink_interval_ticks: 50,
+            shrink_factor: 0.7,
+            min_radius: 2.0,
+            damage_per_tick: 5,
+        }
+    }
+}
+
+/// Upper limit of `max_players`, which keeps the formations computed for every player count small.
+const MAX_PLAYERS: usize = 100;
+/// Upper limit of `field_size_x` and `field_size_y`, which keeps generated maps cheap to compute.
+const MAX_FIELD_SIZE: i32 = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct GameConfig {
+    pub field_size_x: i32,
+    pub field_size_y: i32,
+    pub max_rounds: i32,
+    pub tick_length_milli_seconds: u64,
+    pub projectile_speed: f64,
+    pub damage_per_hit: i16,
+    pub starting_health: i16,
+    pub max_players: usize,
+    pub seed: Option<u64>,
+    /// Name of the map the layout was taken from. `None` if the layout was configured directly.
+    pub map: Option<String>,
+    /// Seed the map was generated from, if the map `generated` was chosen.
+    pub map_seed: Option<u64>,
+    pub obstacles: Vec<Obstacle>,
+    #[serde(deserialize_with = "maps::deserialize_spawns")]
+    pub spawns: BTreeMap<usize, Vec<Spawn>>,
+    pub pickups: Vec<PickupLocation>,
+    /// Number of ticks between two pickups appearing on a free pickup location.
+    pub pickup_spawn_interval_ticks: i32,
+    pub pickup_health: i16,
+    pub pickup_shield: i16,
+    /// Number of ticks a speed or damage boost lasts.
+    pub pickup_boost_ticks: i32,
+    /// Number of projectiles a player can fire before reloading.
+    pub max_ammo: i32,
+    /// Number of ticks a `RELOAD` takes, including the tick of the action.
+    pub reload_ticks: i32,
+    /// Number of ticks between two projectiles being regenerated. `0` disables the regeneration.
+    pub ammo_regeneration_ticks: i32,
+    /// Number of ticks a player has to wait after shooting before shooting again.
+    pub shot_cooldown_ticks: i32,
+    /// Distance after which projectiles disappear. `None` if projectiles fly until they leave the
+    /// field, weapons with a shorter range keep their range.
+    pub projectile_max_range: Option<f64>,
+    /// Number of ticks after which projectiles disappear. `None` if projectiles fly until they
+    /// leave the field.
+    pub projectile_max_lifetime_ticks: Option<i32>,
+    /// Whether projectiles of different players destroy each other, when they pass the same field
+    /// during a tick.
+    pub projectile_annihilation: bool,
+    /// Shrinking safe zone. `None` if the whole field is safe during the whole game.
+    pub zone: Option<ZoneConfig>,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        let map = GameMap::classic();
+
+        GameConfig {
+            field_size_x: map.field_size_x,
+            field_size_y: map.field_size_y,
+            max_rounds: 5000,
+            tick_length_milli_seconds: 500,
+            projectile_speed: 6.0,
+            damage_per_hit: 20,
+            starting_health: 100,
+            max_players: 7,
+            seed: None,
+            map: None,
+            map_seed: None,
+            obstacles: map.obstacles,
+            spawns: map.spawns,
+            pickups: map.pickups,
+            pickup_spawn_interval_ticks: 25,
+            pickup_health: 30,
+            pickup_shield: 40,
+            pickup_boost_ticks: 20,
+            max_ammo: 10,
+            reload_ticks: 3,
+            ammo_regeneration_ticks: 0,
+            shot_cooldown_ticks: 1,
+            projectile_max_range: None,
+            projectile_max_lifetime_ticks: None,
+            projectile_annihilation: false,
+            zone: None,
+        }
+    }
+}
+
+/// Deserializes a configuration like the derived implementation, but drops the layout of the
+/// default map for a custom field size, as it is made for the default field size. Players are then
+/// placed on a ring around the center of the field, unless the layout is configured directly or
+/// replaced by a map.
+pub fn deserialize_config<'de, D>(deserializer: D) -> Result<GameConfig, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    let is_configured = |key: &str| value.get(key).is_some();
+
+    let has_spawns = is_configured("spawns");
+    let has_obstacles = is_configured("obstacles");
+    let has_pickups = is_configured("pickups");
+
+    let mut config = GameConfig::deserialize(value).map_err(serde::de::Error::custom)?;
+    let default_map = GameMap::classic();
+
+    if config.field_size_x == default_map.field_size_x
+        && config.field_size_y == default_map.field_size_y
+    {
+        return Ok(config);
+    }
+
+    if !has_spawns {
+        config.spawns = BTreeMap::new();
+    }
+
+    if !has_obstacles {
+        config.obstacles = vec![];
+    }
+
+    if !has_pickups {
+        config.pickups = vec![];
+    }
+
+    return Ok(config);
+}
+
+impl GameConfig {
+    /// Checks the field size and player limit, which have to be valid before a map is generated
+    /// for the configuration.
+    pub fn validate_limits(&self) -> Result<(), String> {
+        if self.field_size_x <= 0
+            || self.field_size_y <= 0
+            || self.field_size_x > MAX_FIELD_SIZE
+            || self.field_size_y > MAX_FIELD_SIZE
+        {
+            return Err(format!(
+                "'field_size_x' and 'field_size_y' must be within range (1 - {})",
+                MAX_FIELD_SIZE
+            ));
+        }
+
+        if self.max_players == 0 || self.max_players > MAX_PLAYERS {
+            return Err(format!(
+                "'max_players' must be within range (1 - {})",
+                MAX_PLAYERS
+            ));
+        }
+
+        return Ok(());
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.validate_limits()?;
+
+        if self.max_rounds <= 0 {
+            return Err("'max_rounds' must be greater than 0".to_string());
+        }
+
+        if self.tick_length_milli_seconds == 0 {
+            return Err("'tick_length_milli_seconds' must be greater than 0".to_string());
+        }
+
+        if !self.projectile_speed.is_finite() || self.projectile_speed <= 0.0 {
+            return Err("'projectile_speed' must be greater than 0".to_string());
+        }
+
+        if self.damage_per_hit < 0 {
+            return Err("'damage_per_hit' must not be negative".to_string());
+        }
+
+        if self.starting_health <= 0 {
+            return Err("'starting_health' must be greater than 0".to_string());
+        }
+
+        if self.pickup_spawn_interval_ticks <= 0 {
+            return Err("'pickup_spawn_interval_ticks' must be greater than 0".to_string());
+        }
+
+        if self.pickup_health < 0 || self.pickup_shield < 0 || self.pickup_boost_ticks < 0 {
+            return Err(
+                "'pickup_health', 'pickup_shield' and 'pickup_boost_ticks' must not be negative"
+                    .to_string(),
+            );
+        }
+
+        if self.max_ammo <= 0 {
+            return Err("'max_ammo' must be greater than 0".to_string());
+        }
+
+        if self.reload_ticks <= 0 {
+            return Err("'reload_ticks' must be greater than 0".to_string());
+        }
+
+        if self.ammo_regeneration_ticks < 0 || self.shot_cooldown_ticks < 0 {
+            return Err(
+                "'ammo_regeneration_ticks' and 'shot_cooldown_ticks' must not be negative"
+                    .to_string(),
+            );
+        }
+
+        if self
+            .projectile_max_range
+            .is_some_and(|range| !range.is_finite() || range <= 0.0)
+        {
+            return Err("'projectile_max_range' must be greater than 0".to_string());
+        }
+
+        if self
+            .projectile_max_lifetime_ticks
+            .is_some_and(|ticks| ticks <= 0)
+        {
+            return Err("'projectile_max_lifetime_ticks' must be greater than 0".to_string());
+        }
+
+        if let Some(zone) = &self.zone {
+            zone.validate()?;
+        }
+
+        for (player_count, spawns) in &self.spawns {
+            if spawns.len() != *player_count {
+                return Err(format!(
+                    "Formation for {} players must contain exactly {} spawns",
+                    player_count, player_count
+                ));
+            }
+
+            for spawn in spawns {
+                if !self.is_within_field(spawn.x, spawn.y) {
+                    return Err(format!(
+                        "Spawn at ({}, {}) is outside of the field",
+                        spawn.x, spawn.y
+                    ));
+                }
+            }
+        }
+
+        for obstacle in &self.obstacles {
+            if !self.is_within_field(obstacle.x, obstacle.y) {
+                return Err(format!(
+                    "Obstacle at ({}, {}) is outside of the field",
+                    obstacle.x, obstacle.y
+                ));
+            }
+        }
+
+        for player_count in 1..=self.max_players {
+            for spawn in formations::get_starting_positions(self, player_count)? {
+                if self
+                    .obstacles
+                    .iter()
+                    .any(|obstacle| obstacle.x == spawn.x && obstacle.y == spawn.y)
+                {
+                    return Err(format!(
+                        "Obstacle at ({}, {}) blocks a starting position",
+                        spawn.x, spawn.y
+                    ));
+                }
+            }
+        }
+
+        for pickup in &self.pickups {
+            if !self.is_within_field(pickup.x, pickup.y) {
+                return Err(format!(
+                    "Pickup location at ({}, {}) is outside of the field",
+                    pickup.x, pickup.y
+                ));
+            }
+
+            if self
+                .obstacles
+                .iter()
+                .any(|obstacle| obstacle.x == pickup.x && obstacle.y == pickup.y)
+            {
+                return Err(format!(
+                    "Pickup location at ({}, {}) is blocked by an obstacle",
+                    pickup.x, pickup.y
+                ));
+            }
+        }
+
+        return Ok(());
+    }
+
+    fn is_within_field(&self, x: i32, y: i32) -> bool {
+        return x >= 0 && x < self.field_size_x && y >= 0 && y < self.field_size_y;
+    }
+}
+
+impl ZoneConfig {
+    fn validate(&self) -> Result<(), String> {
+        if self.shrink_start_ticks <= 0 || self.shrink_interval_ticks <= 0 {
+            return Err(
+                "'zone.shrink_start_ticks' and 'zone.shrink_interval_ticks' must be greater than 0"
+                    .to_string(),
+            );
+        }
+
+        if !(self.shrink_factor > 0.0 && self.shrink_factor < 1.0) {
+            return Err("'zone.shrink_factor' must be within range (0.0 - 1.0)".to_string());
+        }
+
+        if !self.min_radius.is_finite() || self.min_radius < 0.0 {
+            return Err("'zone.min_radius' must not be negative".to_string());
+        }
+
+        if self.damage_per_tick < 0 {
+            return Err("'zone.damage_per_tick' must not be negative".to_string());
+        }
+
+        return Ok(());
+    }
+}
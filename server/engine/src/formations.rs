@@ -0,0 +1,119 @@
+use std::f64::consts::PI;
+
+use crate::config::GameConfig;
+use crate::models::Spawn;
+
+const CLASSIC_COLORS: [&str; 7] = [
+    "#FF0000", "#00FF00", "#0000FF", "#C800FF", "#00FFE1", "#FF9D00", "#0F754C",
+];
+/// Hue difference between consecutive generated colors, which keeps any number of colors apart.
+const GOLDEN_ANGLE: f64 = 137.508;
+/// Distance of the spawns to the center, relative to the distance of the center to the border.
+const SPAWN_RING_RADIUS: f64 = 0.65;
+
+/// Starting positions for the player count. Formations configured by the map are used as they
+/// are, for any other player count the players are placed on a ring around the center of the
+/// field.
+pub fn get_starting_positions(
+    config: &GameConfig,
+    player_count: usize,
+) -> Result<Vec<Spawn>, String> {
+    if player_count == 0 {
+        return Ok(vec![]);
+    }
+
+    return match config.spawns.get(&player_count) {
+        Some(positions) => Ok(positions.clone()),
+        None => get_ring_formation(config.field_size_x, config.field_size_y, player_count),
+    };
+}
+
+/// Places the players evenly on a ring around the center of the field, facing the center. The
+/// first player starts on the left side of the field, a single player in the center.
+pub fn get_ring_formation(
+    field_size_x: i32,
+    field_size_y: i32,
+    player_count: usize,
+) -> Result<Vec<Spawn>, String> {
+    let center_x = (field_size_x - 1) as f64 / 2.0;
+    let center_y = (field_size_y - 1) as f64 / 2.0;
+
+    if player_count == 1 {
+        return Ok(vec![Spawn {
+            x: center_x.floor() as i32,
+            y: center_y.floor() as i32,
+            rotation: 0,
+        }]);
+    }
+
+    let mut formation: Vec<Spawn> = vec![];
+
+    for index in 0..player_count {
+        // Angles follow the rotation of the players, 0 degrees points up and 90 degrees right
+        let degrees = 270.0 + 360.0 * index as f64 / player_count as f64;
+        let radians = degrees * PI / 180.0;
+
+        let x = (center_x + SPAWN_RING_RADIUS * center_x * radians.sin()).round() as i32;
+        let y = (center_y + SPAWN_RING_RADIUS * center_y * radians.cos()).round() as i32;
+
+        if formation.iter().any(|spawn| spawn.x == x && spawn.y == y) {
+            return Err(format!(
+                "The field is too small to place {} players on a ring",
+                player_count
+            ));
+        }
+
+        let rotation = (center_x - x as f64)
+            .atan2(center_y - y as f64)
+            .to_degrees()
+            .round() as i32;
+
+        formation.push(Spawn {
+            x,
+            y,
+            rotation: (rotation + 360) % 360,
+        });
+    }
+
+    return Ok(formation);
+}
+
+/// Color of the n-th player joining a lobby, starting with 1. The first seven players get the
+/// classic colors, the colors of all further players are spread over the color wheel.
+pub fn get_color(player_number: usize) -> String {
+    if let Some(color) = CLASSIC_COLORS.get(player_number.wrapping_sub(1)) {
+        return color.to_string();
+    }
+
+    let hue = (player_number as f64 * GOLDEN_ANGLE) % 360.0;
+    // Every other round of the color wheel is darker, so similar hues can still be told apart
+    let value = match (player_number as f64 * GOLDEN_ANGLE / 360.0) as usize % 2 {
+        0 => 0.95,
+        _ => 0.7,
+    };
+
+    let (red, green, blue) = hsv_to_rgb(hue, 0.85, value);
+
+    return format!("#{:02X}{:02X}{:02X}", red, green, blue);
+}
+
+fn hsv_to_rgb(hue: f64, saturation: f64, value: f64) -> (u8, u8, u8) {
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let m = value - chroma;
+
+    let (red, green, blue) = match (hue / 60.0) as i32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    return (
+        ((red + m) * 255.0).round() as u8,
+        ((green + m) * 255.0).round() as u8,
+        ((blue + m) * 255.0).round() as u8,
+    );
+}
@@ -0,0 +1,185 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use crate::formations;
+use crate::maps::{GameMap, GENERATED_MAP_NAME};
+use crate::models::{Obstacle, PickupLocation};
+
+const MIN_FIELD_SIZE: i32 = 10;
+/// Number of cells of a quarter of the field per obstacle cluster.
+const CELLS_PER_CLUSTER: i32 = 40;
+const MAX_CLUSTERS: i32 = 100;
+const MIN_CLUSTER_SIZE: usize = 2;
+const MAX_CLUSTER_SIZE: usize = 6;
+/// Number of cells of the field per group of four mirrored pickup locations.
+const CELLS_PER_PICKUP_GROUP: i32 = 450;
+
+/// Generates a random but fair map from a seed. Obstacles and pickup locations are mirrored along
+/// both axes of the field, the spawns of every formation are evenly distributed on a ring around
+/// the center of the field, facing the center.
+///
+/// Clusters of obstacles are only placed if every spawn stays reachable from every other spawn.
+pub fn generate_map(
+    seed: u64,
+    field_size_x: i32,
+    field_size_y: i32,
+    max_players: usize,
+) -> Result<GameMap, String> {
+    if field_size_x < MIN_FIELD_SIZE || field_size_y < MIN_FIELD_SIZE {
+        return Err(format!(
+            "Generated maps need a field of at least {} x {}",
+            MIN_FIELD_SIZE, MIN_FIELD_SIZE
+        ));
+    }
+
+    if max_players == 0 {
+        return Err("Generated maps need at least 1 player".to_string());
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut spawns = BTreeMap::new();
+
+    for player_count in 1..=max_players {
+        spawns.insert(
+            player_count,
+            formations::get_ring_formation(field_size_x, field_size_y, player_count)?,
+        );
+    }
+
+    // Spawns and their neighbouring fields are kept free, so no player starts boxed in
+    let protected: HashSet<(i32, i32)> = spawns
+        .values()
+        .flatten()
+        .flat_map(|spawn| {
+            (-1..=1).flat_map(move |dx| (-1..=1).map(move |dy| (spawn.x + dx, spawn.y + dy)))
+        })
+        .collect();
+
+    let spawn_fields: Vec<(i32, i32)> = spawns
+        .values()
+        .flatten()
+        .map(|spawn| (spawn.x, spawn.y))
+        .collect();
+
+    let first_spawn_field = match spawn_fields.first() {
+        Some(field) => *field,
+        None => return Err("Generated maps need at least 1 spawn".to_string()),
+    };
+
+    let quarter_x = (field_size_x + 1) / 2;
+    let quarter_y = (field_size_y + 1) / 2;
+    let cluster_count = std::cmp::min(quarter_x * quarter_y / CELLS_PER_CLUSTER, MAX_CLUSTERS);
+
+    let mut obstacles: HashSet<(i32, i32)> = HashSet::new();
+
+    for _ in 0..cluster_count {
+        let cluster_size = rng.gen_range(MIN_CLUSTER_SIZE..=MAX_CLUSTER_SIZE);
+        let mut x = rng.gen_range(0..quarter_x);
+        let mut y = rng.gen_range(0..quarter_y);
+        let mut cluster: HashSet<(i32, i32)> = HashSet::new();
+
+        // A cluster is a random walk through a quarter of the field, mirrored to all quarters
+        for _ in 0..cluster_size {
+            for field in mirror(x, y, field_size_x, field_size_y) {
+                if !protected.contains(&field) {
+                    cluster.insert(field);
+                }
+            }
+
+            match rng.gen_range(0..4) {
+                0 => x = std::cmp::min(x + 1, quarter_x - 1),
+                1 => x = std::cmp::max(x - 1, 0),
+                2 => y = std::cmp::min(y + 1, quarter_y - 1),
+                _ => y = std::cmp::max(y - 1, 0),
+            }
+        }
+
+        let mut candidate = obstacles.clone();
+        candidate.extend(cluster);
+
+        let reachable = reachable_fields(&candidate, first_spawn_field, field_size_x, field_size_y);
+
+        if spawn_fields.iter().all(|field| reachable.contains(field)) {
+            obstacles = candidate;
+        }
+    }
+
+    let reachable = reachable_fields(&obstacles, first_spawn_field, field_size_x, field_size_y);
+    let mut pickups: Vec<PickupLocation> = vec![];
+    let pickup_group_count = std::cmp::max(1, field_size_x * field_size_y / CELLS_PER_PICKUP_GROUP);
+
+    for _ in 0..pickup_group_count {
+        let x = rng.gen_range(0..quarter_x);
+        let y = rng.gen_range(0..quarter_y);
+
+        if !reachable.contains(&(x, y)) || protected.contains(&(x, y)) {
+            continue;
+        }
+
+        for (x, y) in mirror(x, y, field_size_x, field_size_y) {
+            if !pickups.iter().any(|pickup| pickup.x == x && pickup.y == y) {
+                pickups.push(PickupLocation { x, y });
+            }
+        }
+    }
+
+    let mut obstacles: Vec<Obstacle> = obstacles
+        .into_iter()
+        .map(|(x, y)| Obstacle { x, y })
+        .collect();
+    obstacles.sort_by_key(|obstacle| (obstacle.x, obstacle.y));
+
+    return Ok(GameMap {
+        name: GENERATED_MAP_NAME.to_string(),
+        field_size_x,
+        field_size_y,
+        max_players: Some(max_players),
+        obstacles,
+        spawns,
+        pickups,
+    });
+}
+
+/// The field together with its mirror images along both axes of the field.
+fn mirror(x: i32, y: i32, field_size_x: i32, field_size_y: i32) -> Vec<(i32, i32)> {
+    let mirrored_x = field_size_x - 1 - x;
+    let mirrored_y = field_size_y - 1 - y;
+
+    return vec![
+        (x, y),
+        (mirrored_x, y),
+        (x, mirrored_y),
+        (mirrored_x, mirrored_y),
+    ];
+}
+
+/// All fields a player can walk to from the start field without crossing an obstacle.
+fn reachable_fields(
+    obstacles: &HashSet<(i32, i32)>,
+    start: (i32, i32),
+    field_size_x: i32,
+    field_size_y: i32,
+) -> HashSet<(i32, i32)> {
+    let mut reachable = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some((x, y)) = queue.pop_front() {
+        for next in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+            if next.0 < 0
+                || next.0 >= field_size_x
+                || next.1 < 0
+                || next.1 >= field_size_y
+                || obstacles.contains(&next)
+            {
+                continue;
+            }
+
+            if reachable.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+
+    return reachable;
+}
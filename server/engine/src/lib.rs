@@ -0,0 +1,452 @@
+#![allow(clippy::needless_return, clippy::upper_case_acronyms)]
+
+//! Rules of the game, free of any networking, timing or file system access.
+//!
+//! The server drives a [`GameEngine`] per lobby, but the engine can just as well be embedded into
+//! tests or training harnesses. Given the same seed, players and actions, the engine always
+//! produces the same game states.
+
+use indexmap::IndexMap;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+mod actions;
+pub mod collision;
+mod config;
+mod formations;
+mod generator;
+mod maps;
+mod models;
+pub mod protocol;
+mod rules;
+mod weapons;
+mod zone;
+
+pub use actions::{ClientAction, PlayerAction};
+pub use config::{deserialize_config, GameConfig, ZoneConfig};
+pub use maps::{GameMap, GENERATED_MAP_NAME};
+pub use models::{
+    Entity, EntityType, Obstacle, Pickup, PickupLocation, PickupType, Player, PlayerStats,
+    Projectile, ProjectileType, Spawn, Standing, WeaponType, Zone, ZoneBounds, ZoneShape,
+};
+
+#[derive(Clone)]
+pub struct GameState {
+    pub players: IndexMap<Uuid, Player>,
+    /// Projectiles and pickups on the field.
+    pub entities: Vec<Entity>,
+    pub obstacles: Vec<Obstacle>,
+    pub zone: Option<Zone>,
+}
+
+impl GameState {
+    pub fn projectiles(&self) -> impl Iterator<Item = &Projectile> {
+        return self.entities.iter().filter_map(|entity| match entity {
+            Entity::PROJECTILE(projectile) => Some(projectile),
+            _ => None,
+        });
+    }
+
+    pub fn pickups(&self) -> impl Iterator<Item = &Pickup> {
+        return self.entities.iter().filter_map(|entity| match entity {
+            Entity::PICKUP(pickup) => Some(pickup),
+            _ => None,
+        });
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum FinishReason {
+    MAX_ROUNDS_REACHED,
+    LAST_PLAYER_STANDING,
+}
+
+#[derive(Debug, Clone)]
+pub struct TickOutcome {
+    pub round: i32,
+    pub tick: Uuid,
+    pub finish_reason: Option<FinishReason>,
+}
+
+#[derive(Clone)]
+pub struct GameEngine {
+    config: GameConfig,
+    game_state: GameState,
+    rng: StdRng,
+    round: i32,
+    tick: Uuid,
+    finish_reason: Option<FinishReason>,
+    eliminated_in_round: HashMap<Uuid, i32>,
+}
+
+impl GameEngine {
+    /// Creates an engine without any players. If the configuration has no seed, a random seed is
+    /// chosen and stored in the configuration.
+    pub fn new(mut config: GameConfig) -> GameEngine {
+        let seed = *config.seed.get_or_insert_with(rand::random);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let tick = generate_uuid(&mut rng);
+        let obstacles = config.obstacles.clone();
+        let zone = config
+            .zone
+            .as_ref()
+            .map(|zone_config| zone::create_zone(&config, zone_config));
+
+        return GameEngine {
+            config,
+            game_state: GameState {
+                players: IndexMap::new(),
+                entities: Vec::new(),
+                obstacles,
+                zone,
+            },
+            rng,
+            round: 0,
+            tick,
+            finish_reason: None,
+            eliminated_in_round: HashMap::new(),
+        };
+    }
+
+    /// Creates an engine with an existing roster, e.g. to simulate a recorded game again.
+    pub fn with_players(config: GameConfig, players: Vec<Player>) -> GameEngine {
+        let mut engine = GameEngine::new(config);
+
+        engine.game_state.players = players
+            .into_iter()
+            .map(|player| (player.id, player))
+            .collect();
+
+        return engine;
+    }
+
+    pub fn config(&self) -> &GameConfig {
+        return &self.config;
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.config.seed.unwrap();
+    }
+
+    pub fn round(&self) -> i32 {
+        return self.round;
+    }
+
+    pub fn tick(&self) -> Uuid {
+        return self.tick;
+    }
+
+    pub fn finish_reason(&self) -> Option<FinishReason> {
+        return self.finish_reason;
+    }
+
+    pub fn game_state(&self) -> &GameState {
+        return &self.game_state;
+    }
+
+    /// Players in the order they joined the game.
+    pub fn players(&self) -> impl Iterator<Item = &Player> {
+        return self.game_state.players.values();
+    }
+
+    pub fn player(&self, player_id: &Uuid) -> Option<&Player> {
+        return self.game_state.players.get(player_id);
+    }
+
+    pub fn player_count(&self) -> usize {
+        return self.game_state.players.len();
+    }
+
+    pub fn players_alive_count(&self) -> usize {
+        return self
+            .game_state
+            .players
+            .values()
+            .filter(|player| player.health > 0)
+            .count();
+    }
+
+    /// The player with the most health, which is the last player alive once the game is over.
+    /// `None` if no player is alive or several players share the highest health.
+    pub fn winner(&self) -> Option<&Player> {
+        let highest_health = self.players().map(|player| player.health).max()?;
+
+        if highest_health <= 0 {
+            return None;
+        }
+
+        let mut leaders = self
+            .players()
+            .filter(|player| player.health == highest_health);
+        let leader = leaders.next();
+
+        if leaders.next().is_some() {
+            return None;
+        }
+
+        return leader;
+    }
+
+    /// Placements of all players, best placement first.
+    pub fn standings(&self) -> Vec<Standing> {
+        // Alive players are ranked before eliminated players, later eliminations rank better
+        let rank_key = |player: &Player| match self.eliminated_in_round.get(&player.id) {
+            Some(round) => (1, -round, 0),
+            None => (0, 0, -(player.health as i32)),
+        };
+
+        let mut players: Vec<&Player> = self.players().collect();
+        players.sort_by_key(|player| rank_key(player));
+
+        let mut standings: Vec<Standing> = vec![];
+
+        for (index, player) in players.iter().enumerate() {
+            let placement = match standings.last() {
+                Some(previous) if rank_key(players[index - 1]) == rank_key(player) => {
+                    previous.placement
+                }
+                _ => index + 1,
+            };
+
+            standings.push(Standing {
+                placement,
+                player_id: player.id,
+                name: player.name.clone(),
+                health: player.health,
+                eliminated_in_round: self.eliminated_in_round.get(&player.id).cloned(),
+                stats: player.stats.clone(),
+            });
+        }
+
+        return standings;
+    }
+
+    pub fn add_player(&mut self, name: String) -> Result<Uuid, String> {
+        let player_count = self.player_count();
+
+        if player_count >= self.config.max_players {
+            return Err(format!(
+                "Cannot add player, because lobby is limited to {} players.",
+                self.config.max_players
+            ));
+        }
+
+        let color = formations::get_color(player_count + 1);
+
+        let player_id = generate_uuid(&mut self.rng);
+
+        let new_player = Player {
+            entity_type: EntityType::PLAYER,
+            damage_inflicted_by: vec![],
+            id: player_id,
+            name,
+            x: 0,
+            y: 0,
+            rotation: 100,
+            color,
+            health: self.config.starting_health,
+            last_action_success: true,
+            error_message: "".to_string(),
+            stats: PlayerStats::default(),
+            shield: 0,
+            speed_boost_ticks: 0,
+            damage_boost_ticks: 0,
+            ammo: self.config.max_ammo,
+            reload_ticks: 0,
+            shot_cooldown_ticks: 0,
+            weapon: WeaponType::default(),
+        };
+
+        self.game_state.players.insert(player_id, new_player);
+
+        if let Err(error_message) = self.assign_starting_positions() {
+            self.game_state.players.shift_remove(&player_id);
+            return Err(error_message);
+        }
+
+        return Ok(player_id);
+    }
+
+    pub fn remove_player(&mut self, player_id: &Uuid) -> Option<Player> {
+        return self.game_state.players.shift_remove(player_id);
+    }
+
+    /// Takes a player out of a running game, e.g. because the player left it. The player stays in
+    /// the game without health, so the next tick records the elimination for the standings.
+    pub fn eliminate_player(&mut self, player_id: &Uuid) {
+        if let Some(player) = self.game_state.players.get_mut(player_id) {
+            player.health = 0;
+        }
+    }
+
+    /// Places all players on the starting formation for the current player count.
+    pub fn assign_starting_positions(&mut self) -> Result<(), String> {
+        let starting_positions =
+            formations::get_starting_positions(&self.config, self.player_count())?;
+
+        self.game_state
+            .players
+            .values_mut()
+            .zip(starting_positions)
+            .for_each(|(player, spawn)| {
+                player.x = spawn.x;
+                player.y = spawn.y;
+                player.rotation = spawn.rotation;
+            });
+
+        return Ok(());
+    }
+
+    /// Resets the game to its first round, keeping the roster of players.
+    pub fn start(&mut self) {
+        self.rng = StdRng::seed_from_u64(self.seed());
+        self.tick = generate_uuid(&mut self.rng);
+        self.round = 0;
+        self.finish_reason = None;
+        self.eliminated_in_round = HashMap::new();
+
+        self.game_state.entities = vec![];
+        self.game_state.zone = self
+            .config
+            .zone
+            .as_ref()
+            .map(|zone_config| zone::create_zone(&self.config, zone_config));
+
+        let _ = self.assign_starting_positions();
+
+        let starting_health = self.config.starting_health;
+        let max_ammo = self.config.max_ammo;
+        self.game_state.players.values_mut().for_each(|player| {
+            player.health = starting_health;
+            player.stats = PlayerStats::default();
+            player.shield = 0;
+            player.speed_boost_ticks = 0;
+            player.damage_boost_ticks = 0;
+            player.ammo = max_ammo;
+            player.reload_ticks = 0;
+            player.shot_cooldown_ticks = 0;
+            player.weapon = WeaponType::default();
+        });
+    }
+
+    /// Applies the actions of one tick in the order the players joined the game, updates reloads
+    /// and cooldowns, collects pickups, moves all projectiles, damages players outside of the zone,
+    /// spawns new pickups, shrinks the zone and advances to the next tick. Players without an
+    /// action stay idle.
+    pub fn apply_actions(&mut self, actions: &HashMap<Uuid, PlayerAction>) -> TickOutcome {
+        let player_ids: Vec<Uuid> = self.game_state.players.keys().cloned().collect();
+        let start_positions: HashMap<Uuid, (i32, i32)> = self
+            .game_state
+            .players
+            .values()
+            .map(|player| (player.id, (player.x, player.y)))
+            .collect();
+
+        for player_id in player_ids {
+            if let Some(player_action) = actions.get(&player_id) {
+                rules::handle_player_action(
+                    player_action,
+                    &player_id,
+                    &mut self.game_state,
+                    &self.config,
+                    &mut self.rng,
+                );
+            }
+        }
+
+        self.game_state.players.values_mut().for_each(|player| {
+            player.damage_inflicted_by = vec![];
+            player.speed_boost_ticks = std::cmp::max(0, player.speed_boost_ticks - 1);
+            player.damage_boost_ticks = std::cmp::max(0, player.damage_boost_ticks - 1);
+        });
+
+        rules::update_ammo(&mut self.game_state, &self.config, self.round + 1);
+        rules::collect_pickups(&mut self.game_state, &self.config);
+        rules::calculate_projectile_updates(&mut self.game_state, &self.config, &start_positions);
+
+        if let Some(zone_config) = &self.config.zone {
+            zone::apply_zone_damage(&mut self.game_state, zone_config);
+        }
+
+        self.game_state
+            .players
+            .values_mut()
+            .filter(|player| player.health > 0)
+            .for_each(|player| player.stats.survival_ticks += 1);
+
+        rules::spawn_pickups(
+            &mut self.game_state,
+            &self.config,
+            self.round + 1,
+            &mut self.rng,
+        );
+
+        if let (Some(zone), Some(zone_config)) = (&mut self.game_state.zone, &self.config.zone) {
+            zone::update_zone(zone, zone_config);
+        }
+
+        return self.advance_tick();
+    }
+
+    /// Advances to the next tick without applying any actions.
+    pub fn advance_tick(&mut self) -> TickOutcome {
+        self.tick = generate_uuid(&mut self.rng);
+        self.round += 1;
+
+        for player in self.game_state.players.values() {
+            if player.health <= 0 && !self.eliminated_in_round.contains_key(&player.id) {
+                self.eliminated_in_round.insert(player.id, self.round);
+            }
+        }
+
+        if self.finish_reason.is_none() {
+            if self.round >= self.config.max_rounds {
+                self.finish_reason = Some(FinishReason::MAX_ROUNDS_REACHED);
+            } else if self.players_alive_count() <= 1 && self.player_count() > 1 {
+                self.finish_reason = Some(FinishReason::LAST_PLAYER_STANDING);
+            }
+        }
+
+        return TickOutcome {
+            round: self.round,
+            tick: self.tick,
+            finish_reason: self.finish_reason,
+        };
+    }
+
+    pub fn game_state_out(&self, spectators: i32) -> protocol::GameStateOut {
+        return protocol::GameStateOut {
+            tick: self.tick,
+            tick_length_milli_seconds: self.config.tick_length_milli_seconds,
+            field_size_x: self.config.field_size_x,
+            field_size_y: self.config.field_size_y,
+            players: self.game_state.players.values().cloned().collect(),
+            entities: self.game_state.entities.clone(),
+            obstacles: self.game_state.obstacles.clone(),
+            zone: self.game_state.zone.clone(),
+            spectators,
+        };
+    }
+
+    pub fn game_over(&self, reason: protocol::GameOverReason) -> protocol::GameOver {
+        let winner_id = match reason {
+            protocol::GameOverReason::STOPPED_BY_ADMIN => None,
+            _ => self.winner().map(|player| player.id),
+        };
+
+        return protocol::GameOver {
+            reason,
+            winner_id,
+            round: self.round,
+            standings: self.standings(),
+        };
+    }
+}
+
+pub(crate) fn generate_uuid(rng: &mut StdRng) -> Uuid {
+    return uuid::Builder::from_random_bytes(rng.gen()).into_uuid();
+}
@@ -0,0 +1,128 @@
+use lazy_static::lazy_static;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+
+use crate::config::GameConfig;
+use crate::generator;
+use crate::models::{Obstacle, PickupLocation, Spawn};
+
+/// Name of the map, which is generated from the map seed of the configuration.
+pub const GENERATED_MAP_NAME: &str = "generated";
+
+lazy_static! {
+    static ref CLASSIC_MAP: GameMap = serde_json::from_str(include_str!("../maps/classic.json"))
+        .expect("Built-in map 'classic' is invalid");
+}
+
+/// Layout of a playing field. `spawns` contains the starting formations of the map, e.g.
+/// `spawns[&2]` lists the starting positions of a game with two players. Player counts without a
+/// formation are placed on a ring around the center of the field.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GameMap {
+    pub name: String,
+    pub field_size_x: i32,
+    pub field_size_y: i32,
+    /// Largest number of players the map is made for. `None` if the map has no limit.
+    #[serde(default)]
+    pub max_players: Option<usize>,
+    #[serde(default)]
+    pub obstacles: Vec<Obstacle>,
+    #[serde(default, deserialize_with = "deserialize_spawns")]
+    pub spawns: BTreeMap<usize, Vec<Spawn>>,
+    #[serde(default)]
+    pub pickups: Vec<PickupLocation>,
+}
+
+impl GameMap {
+    /// The map used when a lobby is created without choosing a map.
+    pub fn classic() -> GameMap {
+        return CLASSIC_MAP.clone();
+    }
+
+    /// Generates a symmetric map with random obstacle clusters. The same seed, field size and
+    /// player limit always result in the same map.
+    pub fn generate(
+        seed: u64,
+        field_size_x: i32,
+        field_size_y: i32,
+        max_players: usize,
+    ) -> Result<GameMap, String> {
+        return generator::generate_map(seed, field_size_x, field_size_y, max_players);
+    }
+
+    /// Replaces the layout of a configuration using the map `generated` with a map generated from
+    /// its field size and player limit. If the configuration has no map seed, a random seed is
+    /// chosen and stored in the configuration.
+    pub fn apply_generated_to(config: &mut GameConfig) -> Result<(), String> {
+        let map_seed = *config.map_seed.get_or_insert_with(rand::random);
+
+        let map = GameMap::generate(
+            map_seed,
+            config.field_size_x,
+            config.field_size_y,
+            config.max_players,
+        )?;
+
+        map.apply_to(config);
+
+        return Ok(());
+    }
+
+    /// Replaces the layout of the configuration with the map. The player limit of the
+    /// configuration is lowered, if the map is made for less players.
+    pub fn apply_to(&self, config: &mut GameConfig) {
+        config.map = Some(self.name.clone());
+        config.field_size_x = self.field_size_x;
+        config.field_size_y = self.field_size_y;
+        config.obstacles = self.obstacles.clone();
+        config.spawns = self.spawns.clone();
+        config.pickups = self.pickups.clone();
+
+        if let Some(max_players) = self.max_players {
+            config.max_players = std::cmp::min(config.max_players, max_players);
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.is_empty() {
+            return Err("Map must have a name".to_string());
+        }
+
+        if self.max_players == Some(0) {
+            return Err(format!("Map '{}' must allow at least 1 player", self.name));
+        }
+
+        let mut config = GameConfig::default();
+        self.apply_to(&mut config);
+
+        return config
+            .validate()
+            .map_err(|error_message| format!("Map '{}' is invalid: {}", self.name, error_message));
+    }
+}
+
+/// JSON objects only have string keys, which are not converted to player counts by serde, when
+/// the spawns are part of a flattened struct like the body of the create lobby request.
+pub(crate) fn deserialize_spawns<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<usize, Vec<Spawn>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let spawns: BTreeMap<String, Vec<Spawn>> = BTreeMap::deserialize(deserializer)?;
+
+    return spawns
+        .into_iter()
+        .map(|(player_count, formation)| {
+            player_count
+                .parse::<usize>()
+                .map(|player_count| (player_count, formation))
+                .map_err(|_| {
+                    serde::de::Error::custom(format!(
+                        "'{}' is not a valid player count",
+                        player_count
+                    ))
+                })
+        })
+        .collect();
+}
@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum EntityType {
+    PLAYER,
+    PROJECTILE,
+    PICKUP,
+}
+
+/// An object on the field apart from the players. Serialized with an `entity_type` property, so
+/// clients can tell the kinds of entities apart.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "entity_type")]
+pub enum Entity {
+    PROJECTILE(Projectile),
+    PICKUP(Pickup),
+}
+
+impl Entity {
+    pub fn entity_type(&self) -> EntityType {
+        return match self {
+            Entity::PROJECTILE(_) => EntityType::PROJECTILE,
+            Entity::PICKUP(_) => EntityType::PICKUP,
+        };
+    }
+}
+
+/// A field of the map, which can neither be entered by players nor passed by projectiles.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Obstacle {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Starting position and rotation of a player.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Spawn {
+    pub x: i32,
+    pub y: i32,
+    pub rotation: i32,
+}
+
+/// A field of the map, on which pickups appear.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct PickupLocation {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum PickupType {
+    /// Restores health, up to the starting health.
+    HEALTH,
+    /// Absorbs damage before the player loses health.
+    SHIELD,
+    /// Lets the player move two fields per movement for a number of ticks.
+    SPEED_BOOST,
+    /// Multiplies the damage of fired projectiles for a number of ticks.
+    DAMAGE_BOOST,
+}
+
+/// A power-up lying on the field, which is collected by moving onto it.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Pickup {
+    pub id: Uuid,
+    pub pickup_type: PickupType,
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ZoneShape {
+    RECTANGLE,
+    CIRCLE,
+}
+
+/// Boundary of a safe zone. Fields whose center lies within the boundary are safe. `radius_x` and
+/// `radius_y` are the distances of the borders of a rectangle to its center, a circle has the same
+/// radius along both axes.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct ZoneBounds {
+    pub center_x: f64,
+    pub center_y: f64,
+    pub radius_x: f64,
+    pub radius_y: f64,
+}
+
+/// Safe zone, which shrinks to the next boundary on a schedule. Players outside of the current
+/// boundary lose health every tick.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Zone {
+    pub shape: ZoneShape,
+    pub current: ZoneBounds,
+    pub next: ZoneBounds,
+    /// Number of ticks until the zone shrinks to the next boundary. `None` if the zone reached its
+    /// smallest size.
+    pub ticks_until_shrink: Option<i32>,
+}
+
+/// Weapon a player shoots with, changed with the `SWITCH_WEAPON` action.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum WeaponType {
+    /// Fires a single fast projectile.
+    #[default]
+    RIFLE,
+    /// Fires a spread of several short-range pellets.
+    SHOTGUN,
+    /// Fires a slow rocket, which explodes on impact and damages all players nearby.
+    ROCKET,
+    /// Fires a beam, which crosses the whole field in the tick it is fired.
+    LASER,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum ProjectileType {
+    #[default]
+    BULLET,
+    PELLET,
+    ROCKET,
+    LASER,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Projectile {
+    pub id: Uuid,
+    pub previous_x: f64,
+    pub previous_y: f64,
+    pub x: f64,
+    pub y: f64,
+    pub travel_distance: f64,
+    pub direction: i32,
+    #[serde(default)]
+    pub projectile_type: ProjectileType,
+    /// Health a hit player loses, raised by a damage boost of the shooter.
+    #[serde(default)]
+    pub damage: i16,
+    /// Distance the projectile flies until it disappears. `None` if it flies until it leaves the
+    /// field.
+    #[serde(default)]
+    pub remaining_range: Option<f64>,
+    /// Number of ticks the projectile flies until it disappears. `None` if it flies until it
+    /// leaves the field.
+    #[serde(default)]
+    pub remaining_ticks: Option<i32>,
+    #[serde(skip)]
+    pub source: Uuid,
+    /// Players the projectile hit. The projectile is removed after hitting players, several players
+    /// are only hit at once if they are hit at the same time.
+    #[serde(skip)]
+    pub hit_players: Vec<Uuid>,
+}
+
+/// Statistics of a player, which are accumulated over the whole game.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct PlayerStats {
+    pub damage_dealt: i32,
+    pub damage_taken: i32,
+    pub kills: i32,
+    pub shots_fired: i32,
+    /// Number of fired projectiles, which hit at least one player.
+    pub shots_hit: i32,
+    /// Share of fired projectiles, which hit a player (0.0 - 1.0).
+    pub accuracy: f64,
+    /// Number of ticks the player was alive at the end of the tick.
+    pub survival_ticks: i32,
+}
+
+impl PlayerStats {
+    pub(crate) fn update_accuracy(&mut self) {
+        self.accuracy = if self.shots_fired > 0 {
+            self.shots_hit as f64 / self.shots_fired as f64
+        } else {
+            0.0
+        };
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Player {
+    pub entity_type: EntityType,
+    pub id: Uuid,
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub rotation: i32,
+    pub color: String,
+    pub damage_inflicted_by: Vec<Uuid>,
+    pub health: i16,
+    pub last_action_success: bool,
+    pub error_message: String,
+    #[serde(default)]
+    pub stats: PlayerStats,
+    /// Damage absorbed before the player loses health.
+    #[serde(default)]
+    pub shield: i16,
+    /// Number of ticks the player still moves two fields per movement.
+    #[serde(default)]
+    pub speed_boost_ticks: i32,
+    /// Number of ticks the projectiles of the player still deal increased damage.
+    #[serde(default)]
+    pub damage_boost_ticks: i32,
+    /// Number of projectiles the player can fire before reloading.
+    #[serde(default)]
+    pub ammo: i32,
+    /// Number of ticks until the magazine is refilled. `0` if the player is not reloading.
+    #[serde(default)]
+    pub reload_ticks: i32,
+    /// Number of ticks until the player can shoot again.
+    #[serde(default)]
+    pub shot_cooldown_ticks: i32,
+    #[serde(default)]
+    pub weapon: WeaponType,
+}
+
+/// Final placement of a player. Players alive at the end of the game are ranked by their health,
+/// eliminated players by the round they were eliminated in. Players with the same result share
+/// a placement.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Standing {
+    pub placement: usize,
+    pub player_id: Uuid,
+    pub name: String,
+    pub health: i16,
+    pub eliminated_in_round: Option<i32>,
+    pub stats: PlayerStats,
+}
@@ -0,0 +1,200 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use uuid::Uuid;
+
+use crate::actions::PlayerAction;
+use crate::config::GameConfig;
+use crate::models::{Entity, Obstacle, Player, Projectile, Standing, Zone};
+use crate::FinishReason;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct GameStateOut {
+    pub tick: Uuid,
+    pub tick_length_milli_seconds: u64,
+    /// Size of the playing field, replays recorded before it was sent use the default field size.
+    #[serde(default = "default_field_size_x")]
+    pub field_size_x: i32,
+    #[serde(default = "default_field_size_y")]
+    pub field_size_y: i32,
+    pub players: Vec<Player>,
+    /// Projectiles and pickups, replays recorded before entities were tagged with their
+    /// `entity_type` only contain projectiles.
+    #[serde(deserialize_with = "deserialize_entities")]
+    pub entities: Vec<Entity>,
+    #[serde(default)]
+    pub obstacles: Vec<Obstacle>,
+    /// Shrinking safe zone, `None` if the lobby plays without a zone.
+    #[serde(default)]
+    pub zone: Option<Zone>,
+    pub spectators: i32,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RecordedEntity {
+    TAGGED(Entity),
+    UNTAGGED(Projectile),
+}
+
+fn deserialize_entities<'de, D>(deserializer: D) -> Result<Vec<Entity>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let entities = Vec::<RecordedEntity>::deserialize(deserializer)?;
+
+    return Ok(entities
+        .into_iter()
+        .map(|entity| match entity {
+            RecordedEntity::TAGGED(entity) => entity,
+            RecordedEntity::UNTAGGED(projectile) => Entity::PROJECTILE(projectile),
+        })
+        .collect());
+}
+
+fn default_field_size_x() -> i32 {
+    return GameConfig::default().field_size_x;
+}
+
+fn default_field_size_y() -> i32 {
+    return GameConfig::default().field_size_y;
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum GameOverReason {
+    LAST_PLAYER_STANDING,
+    MAX_ROUNDS_REACHED,
+    STOPPED_BY_ADMIN,
+}
+
+impl From<FinishReason> for GameOverReason {
+    fn from(finish_reason: FinishReason) -> Self {
+        match finish_reason {
+            FinishReason::LAST_PLAYER_STANDING => GameOverReason::LAST_PLAYER_STANDING,
+            FinishReason::MAX_ROUNDS_REACHED => GameOverReason::MAX_ROUNDS_REACHED,
+        }
+    }
+}
+
+/// Why the game ended, who won and the final placements and statistics of all players. Sent as
+/// the last message of a game.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct GameOver {
+    pub reason: GameOverReason,
+    /// `None` if the game ended in a draw or was stopped by an admin.
+    pub winner_id: Option<Uuid>,
+    pub round: i32,
+    pub standings: Vec<Standing>,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ActionRejectedReason {
+    INVALID_MESSAGE,
+    INVALID_TICK,
+    DUPLICATE_ACTION,
+    LOBBY_NOT_RUNNING,
+    NOT_A_PLAYER,
+}
+
+/// Tells a client why a message was not processed.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ActionRejected {
+    pub reason: ActionRejectedReason,
+    pub message: String,
+    /// Tick the server currently accepts actions for.
+    pub tick: Uuid,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ClientHello {
+    pub success: bool,
+    pub message: String,
+    pub player_id: Uuid,
+    /// Secret which allows the player to reconnect to the game after losing the connection.
+    pub session_token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ClientMessage {
+    pub tick: Uuid,
+    #[serde(flatten)]
+    pub player_action: PlayerAction,
+}
+
+/// Protocol version, in which messages are sent without envelope.
+pub const LEGACY_PROTOCOL_VERSION: u32 = 1;
+pub const LATEST_PROTOCOL_VERSION: u32 = 2;
+pub const SUPPORTED_PROTOCOL_VERSIONS: [u32; 2] =
+    [LEGACY_PROTOCOL_VERSION, LATEST_PROTOCOL_VERSION];
+
+/// Wraps a message with its type and the protocol version it was written for.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Envelope<T> {
+    #[serde(flatten)]
+    pub message: T,
+    pub protocol_version: u32,
+}
+
+/// Messages sent by the server to players and spectators.
+#[allow(non_camel_case_types)]
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "type", content = "payload")]
+pub enum ServerMessage {
+    CLIENT_HELLO(ClientHello),
+    GAME_STATE(GameStateOut),
+    GAME_OVER(GameOver),
+    ACTION_REJECTED(ActionRejected),
+}
+
+/// Messages sent by players to the server.
+#[allow(non_camel_case_types)]
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "type", content = "payload")]
+pub enum PlayerMessage {
+    PLAYER_ACTION(ClientMessage),
+}
+
+impl ServerMessage {
+    /// Serializes the message for a client. Clients of the legacy protocol receive the bare
+    /// message, all other clients receive the message wrapped in an envelope.
+    pub fn to_json(&self, protocol_version: u32) -> String {
+        if protocol_version == LEGACY_PROTOCOL_VERSION {
+            return match self {
+                ServerMessage::CLIENT_HELLO(client_hello) => serde_json::to_string(client_hello),
+                ServerMessage::GAME_STATE(game_state_out) => serde_json::to_string(game_state_out),
+                ServerMessage::GAME_OVER(game_over) => serde_json::to_string(game_over),
+                ServerMessage::ACTION_REJECTED(action_rejected) => {
+                    serde_json::to_string(action_rejected)
+                }
+            }
+            .unwrap();
+        }
+
+        return serde_json::to_string(&Envelope {
+            message: self,
+            protocol_version,
+        })
+        .unwrap();
+    }
+}
+
+/// Parses a message of a player, which uses the given protocol version.
+pub fn parse_player_message(text: &str, protocol_version: u32) -> Result<ClientMessage, String> {
+    if protocol_version == LEGACY_PROTOCOL_VERSION {
+        return serde_json::from_str(text).map_err(|err| err.to_string());
+    }
+
+    let envelope: Envelope<PlayerMessage> =
+        serde_json::from_str(text).map_err(|err| err.to_string())?;
+
+    if envelope.protocol_version != protocol_version {
+        return Err(format!(
+            "Message uses protocol version {}, but the connection was opened with protocol version {}",
+            envelope.protocol_version, protocol_version
+        ));
+    }
+
+    return match envelope.message {
+        PlayerMessage::PLAYER_ACTION(client_message) => Ok(client_message),
+    };
+}
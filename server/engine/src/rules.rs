@@ -0,0 +1,685 @@
+use indexmap::IndexMap;
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use uuid::Uuid;
+
+use crate::actions::{ClientAction, PlayerAction};
+use crate::collision;
+use crate::config::GameConfig;
+use crate::models::{
+    Entity, Obstacle, Pickup, PickupLocation, PickupType, Player, Projectile, ProjectileType,
+};
+use crate::weapons::{self, ROCKET_BLAST_RADIUS};
+use crate::{generate_uuid, GameState};
+
+const PICKUP_TYPES: [PickupType; 4] = [
+    PickupType::HEALTH,
+    PickupType::SHIELD,
+    PickupType::SPEED_BOOST,
+    PickupType::DAMAGE_BOOST,
+];
+/// Factor by which a damage boost multiplies the damage of fired projectiles.
+const DAMAGE_BOOST_MULTIPLIER: i16 = 2;
+
+pub fn handle_player_action(
+    player_action: &PlayerAction,
+    player_id: &Uuid,
+    game_state: &mut GameState,
+    config: &GameConfig,
+    rng: &mut StdRng,
+) {
+    let player = game_state.players.get_mut(player_id).unwrap();
+
+    player.error_message = "".to_string();
+    player.last_action_success = true;
+
+    if player.health <= 0 {
+        player.last_action_success = false;
+        player.error_message =
+            "Message was not processed, because player has no more health left".to_string();
+        return;
+    }
+
+    match player_action.action {
+        ClientAction::SHOOT => {
+            let weapon = weapons::get_weapon_stats(player.weapon, config);
+
+            let error_message = if player.reload_ticks > 0 {
+                Some("Cannot SHOOT, because player is reloading".to_string())
+            } else if player.ammo < weapon.ammo_cost {
+                Some(format!(
+                    "Cannot SHOOT, because player has not enough ammo left for the {:?}, RELOAD first",
+                    player.weapon
+                ))
+            } else if player.shot_cooldown_ticks > 0 {
+                Some(format!(
+                    "Cannot SHOOT, because player has to wait {} more tick(s) before shooting again",
+                    player.shot_cooldown_ticks
+                ))
+            } else {
+                None
+            };
+
+            if let Some(error_message) = error_message {
+                player.error_message = error_message;
+                player.last_action_success = false;
+                return;
+            }
+
+            let damage = if player.damage_boost_ticks > 0 {
+                weapon.damage.saturating_mul(DAMAGE_BOOST_MULTIPLIER)
+            } else {
+                weapon.damage
+            };
+
+            for angle in weapon.angles {
+                let new_projectile = Projectile {
+                    travel_distance: weapon.speed,
+                    id: generate_uuid(rng),
+                    previous_x: player.x.into(),
+                    previous_y: player.y.into(),
+                    x: player.x.into(),
+                    y: player.y.into(),
+                    direction: (player.rotation + angle).rem_euclid(360),
+                    projectile_type: weapon.projectile_type,
+                    damage,
+                    remaining_range: match (weapon.range, config.projectile_max_range) {
+                        (Some(range), Some(max_range)) => Some(range.min(max_range)),
+                        (range, max_range) => range.or(max_range),
+                    },
+                    remaining_ticks: config.projectile_max_lifetime_ticks,
+                    source: player.id,
+                    hit_players: vec![],
+                };
+
+                game_state.entities.push(Entity::PROJECTILE(new_projectile));
+            }
+
+            player.ammo -= weapon.ammo_cost;
+            // The cooldown is counted down at the end of this tick already
+            player.shot_cooldown_ticks = config.shot_cooldown_ticks + 1;
+
+            player.stats.shots_fired += weapon.angles.len() as i32;
+            player.stats.update_accuracy();
+        }
+        ClientAction::SWITCH_WEAPON => {
+            if player_action.weapon.is_none() {
+                player.error_message =
+                    "Cannot SWITCH_WEAPON, because no 'weapon' property was supplied".to_string();
+                player.last_action_success = false;
+                return;
+            }
+
+            player.weapon = player_action.weapon.unwrap();
+        }
+        ClientAction::RELOAD => {
+            if player.reload_ticks > 0 {
+                player.error_message =
+                    "Cannot RELOAD, because player is already reloading".to_string();
+                player.last_action_success = false;
+                return;
+            }
+
+            if player.ammo >= config.max_ammo {
+                player.error_message = "Cannot RELOAD, because ammo is already full".to_string();
+                player.last_action_success = false;
+                return;
+            }
+
+            player.reload_ticks = config.reload_ticks;
+        }
+        ClientAction::TURN => {
+            if player_action.degrees.is_none() {
+                player.error_message =
+                    "Cannot TURN, because no 'degrees' property was supplied".to_string();
+                player.last_action_success = false;
+                return;
+            }
+
+            let degrees = player_action.degrees.unwrap();
+
+            if !(0..=360).contains(&degrees) {
+                player.error_message =
+                    "Cannot TURN, because 'degrees' is not within range (0 - 360)".to_string();
+                player.last_action_success = false;
+                return;
+            }
+
+            player.rotation = degrees;
+        }
+        ClientAction::UP => move_player(player, &game_state.obstacles, config, "UP", 0, 1),
+        ClientAction::DOWN => move_player(player, &game_state.obstacles, config, "DOWN", 0, -1),
+        ClientAction::RIGHT => move_player(player, &game_state.obstacles, config, "RIGHT", 1, 0),
+        ClientAction::LEFT => move_player(player, &game_state.obstacles, config, "LEFT", -1, 0),
+    }
+}
+
+/// Moves the player one field, or two fields while a speed boost is active. The second field is
+/// skipped silently, if it is blocked.
+fn move_player(
+    player: &mut Player,
+    obstacles: &[Obstacle],
+    config: &GameConfig,
+    direction_name: &str,
+    delta_x: i32,
+    delta_y: i32,
+) {
+    let steps = if player.speed_boost_ticks > 0 { 2 } else { 1 };
+
+    for step in 0..steps {
+        let x = player.x + delta_x;
+        let y = player.y + delta_y;
+
+        let error_message =
+            if x < 0 || x >= config.field_size_x || y < 0 || y >= config.field_size_y {
+                Some(format!(
+                    "Cannot move {}, because player is at border of field",
+                    direction_name
+                ))
+            } else if is_obstacle(obstacles, x, y) {
+                Some(format!(
+                    "Cannot move {}, because an obstacle is in the way",
+                    direction_name
+                ))
+            } else {
+                None
+            };
+
+        if let Some(error_message) = error_message {
+            if step == 0 {
+                player.error_message = error_message;
+                player.last_action_success = false;
+            }
+
+            return;
+        }
+
+        player.x = x;
+        player.y = y;
+    }
+}
+
+/// Counts down the shot cooldowns and reloads, refilling the ammo of players who finished
+/// reloading. Every `ammo_regeneration_ticks` rounds, players who are not reloading regain one
+/// projectile.
+pub fn update_ammo(game_state: &mut GameState, config: &GameConfig, round: i32) {
+    let regenerates =
+        config.ammo_regeneration_ticks > 0 && round % config.ammo_regeneration_ticks == 0;
+
+    for player in game_state.players.values_mut() {
+        player.shot_cooldown_ticks = std::cmp::max(0, player.shot_cooldown_ticks - 1);
+
+        if player.reload_ticks > 0 {
+            player.reload_ticks -= 1;
+
+            if player.reload_ticks == 0 {
+                player.ammo = config.max_ammo;
+            }
+        } else if regenerates {
+            player.ammo = std::cmp::min(config.max_ammo, player.ammo + 1);
+        }
+    }
+}
+
+/// Players standing on a pickup collect it, in the order the players joined the game.
+pub fn collect_pickups(game_state: &mut GameState, config: &GameConfig) {
+    for player in game_state.players.values_mut() {
+        if player.health <= 0 {
+            continue;
+        }
+
+        let pickup_index = match game_state.entities.iter().position(|entity| {
+            matches!(entity, Entity::PICKUP(pickup) if pickup.x == player.x && pickup.y == player.y)
+        }) {
+            Some(pickup_index) => pickup_index,
+            None => continue,
+        };
+
+        let pickup = match game_state.entities.remove(pickup_index) {
+            Entity::PICKUP(pickup) => pickup,
+            _ => continue,
+        };
+
+        match pickup.pickup_type {
+            PickupType::HEALTH => {
+                player.health = std::cmp::min(
+                    config.starting_health,
+                    player.health.saturating_add(config.pickup_health),
+                );
+            }
+            PickupType::SHIELD => {
+                player.shield = player.shield.saturating_add(config.pickup_shield);
+            }
+            PickupType::SPEED_BOOST => player.speed_boost_ticks = config.pickup_boost_ticks,
+            PickupType::DAMAGE_BOOST => player.damage_boost_ticks = config.pickup_boost_ticks,
+        }
+    }
+}
+
+/// Every `pickup_spawn_interval_ticks` rounds, a random pickup appears on a random pickup
+/// location, which is neither occupied by another pickup nor by a player.
+pub fn spawn_pickups(
+    game_state: &mut GameState,
+    config: &GameConfig,
+    round: i32,
+    rng: &mut StdRng,
+) {
+    if config.pickups.is_empty() || round % config.pickup_spawn_interval_ticks != 0 {
+        return;
+    }
+
+    let free_locations: Vec<&PickupLocation> = config
+        .pickups
+        .iter()
+        .filter(|location| {
+            !game_state
+                .pickups()
+                .any(|pickup| pickup.x == location.x && pickup.y == location.y)
+                && !game_state
+                    .players
+                    .values()
+                    .any(|player| player.x == location.x && player.y == location.y)
+        })
+        .collect();
+
+    if free_locations.is_empty() {
+        return;
+    }
+
+    let location = free_locations[rng.gen_range(0..free_locations.len())];
+    let pickup_type = PICKUP_TYPES[rng.gen_range(0..PICKUP_TYPES.len())];
+
+    game_state.entities.push(Entity::PICKUP(Pickup {
+        id: generate_uuid(rng),
+        pickup_type,
+        x: location.x,
+        y: location.y,
+    }));
+}
+
+/// Moves all projectiles and deals their damage. Hits are detected continuously during the tick,
+/// players are hit if their field overlaps the path of a projectile at any time of the tick while
+/// they move from `start_positions` to their current position.
+pub fn calculate_projectile_updates(
+    game_state: &mut GameState,
+    config: &GameConfig,
+    start_positions: &HashMap<Uuid, (i32, i32)>,
+) {
+    game_state.entities.retain(|entity| {
+        let projectile = match entity {
+            Entity::PROJECTILE(projectile) => projectile,
+            _ => return true,
+        };
+
+        if projectile.x < 0.0 {
+            return false;
+        }
+        if projectile.y < 0.0 {
+            return false;
+        }
+        if projectile.x > config.field_size_x.into() {
+            return false;
+        }
+        if projectile.y > config.field_size_y.into() {
+            return false;
+        }
+        if projectile.remaining_range.is_some_and(|range| range <= 0.0) {
+            return false;
+        }
+        if projectile.remaining_ticks.is_some_and(|ticks| ticks <= 0) {
+            return false;
+        }
+        return true;
+    });
+
+    // Damage dealt by the shooters, whether the hit was a kill and whether it was the first hit of
+    // the projectile. Credited after all projectiles moved, as the shooters are players as well.
+    let mut hits: Vec<(Uuid, i32, bool, bool)> = vec![];
+    let mut removed_projectiles: Vec<Uuid> = vec![];
+
+    let annihilations = if config.projectile_annihilation {
+        get_annihilations(game_state, start_positions)
+    } else {
+        HashMap::new()
+    };
+
+    let projectiles = game_state
+        .entities
+        .iter_mut()
+        .filter_map(|entity| match entity {
+            Entity::PROJECTILE(projectile) => Some(projectile),
+            _ => None,
+        });
+
+    projectiles.for_each(|projectile| {
+        let (start, end, distance) = get_path_of_projectile(projectile);
+
+        // Projectiles only hit players before they reach the first obstacle on their way or are
+        // destroyed by another projectile, and stop there
+        let obstacle_hit = get_obstacle_hit(&game_state.obstacles, start, end)
+            .into_iter()
+            .chain(annihilations.get(&projectile.id).cloned())
+            .min_by(|first, second| first.0.total_cmp(&second.0));
+
+        if obstacle_hit.is_some() {
+            removed_projectiles.push(projectile.id);
+        }
+
+        let obstacle_time = obstacle_hit.map_or(f64::INFINITY, |(time, _)| time);
+
+        let player_hit_times: Vec<(Uuid, f64)> =
+            get_player_hit_times(&game_state.players, start_positions, projectile, start, end)
+                .into_iter()
+                .filter(|(_, time)| *time < obstacle_time)
+                .collect();
+
+        if projectile.projectile_type == ProjectileType::ROCKET {
+            // Rockets explode at the first player they hit or where they stop
+            let player_impact = player_hit_times
+                .iter()
+                .min_by(|first, second| first.1.total_cmp(&second.1))
+                .map(|(_, time)| {
+                    let (x, y) = collision::interpolate(start, end, *time);
+                    (x.round() as i32, y.round() as i32)
+                });
+
+            if let Some(impact) = player_impact.or(obstacle_hit.map(|(_, field)| field)) {
+                removed_projectiles.push(projectile.id);
+                explode_rocket(&mut game_state.players, projectile, impact, &mut hits);
+            }
+        } else if let Some(first_hit_time) = player_hit_times
+            .iter()
+            .map(|(_, time)| *time)
+            .min_by(|first, second| first.total_cmp(second))
+        {
+            // Projectiles stop at the first player they hit, players hit at the same time are hit
+            // together
+            removed_projectiles.push(projectile.id);
+
+            game_state.players.values_mut().for_each(|player| {
+                if player_hit_times
+                    .iter()
+                    .any(|(player_id, time)| *player_id == player.id && *time == first_hit_time)
+                {
+                    hit_player(player, projectile, &mut hits);
+                }
+            });
+        }
+
+        projectile.previous_x = projectile.x;
+        projectile.previous_y = projectile.y;
+        projectile.x = end.0;
+        projectile.y = end.1;
+
+        if let Some(range) = projectile.remaining_range.as_mut() {
+            *range -= distance;
+        }
+
+        if let Some(ticks) = projectile.remaining_ticks.as_mut() {
+            *ticks -= 1;
+        }
+
+        // Rockets reaching their range or lifetime explode where they stop
+        if projectile.projectile_type == ProjectileType::ROCKET
+            && !removed_projectiles.contains(&projectile.id)
+            && (projectile.remaining_range.is_some_and(|range| range <= 0.0)
+                || projectile.remaining_ticks.is_some_and(|ticks| ticks <= 0))
+        {
+            removed_projectiles.push(projectile.id);
+            explode_rocket(
+                &mut game_state.players,
+                projectile,
+                (end.0.round() as i32, end.1.round() as i32),
+                &mut hits,
+            );
+        }
+    });
+
+    game_state.entities.retain(|entity| match entity {
+        Entity::PROJECTILE(projectile) => !removed_projectiles.contains(&projectile.id),
+        _ => true,
+    });
+
+    for (source, damage, is_kill, is_first_hit) in hits {
+        if let Some(shooter) = game_state.players.get_mut(&source) {
+            shooter.stats.damage_dealt += damage;
+
+            if is_kill {
+                shooter.stats.kills += 1;
+            }
+
+            if is_first_hit {
+                shooter.stats.shots_hit += 1;
+                shooter.stats.update_accuracy();
+            }
+        }
+    }
+}
+
+/// Hits every living player around the impact field of a rocket except the shooter.
+fn explode_rocket(
+    players: &mut IndexMap<Uuid, Player>,
+    projectile: &mut Projectile,
+    (impact_x, impact_y): (i32, i32),
+    hits: &mut Vec<(Uuid, i32, bool, bool)>,
+) {
+    players.values_mut().for_each(|player| {
+        if player.health > 0
+            && (player.x - impact_x).abs() <= ROCKET_BLAST_RADIUS
+            && (player.y - impact_y).abs() <= ROCKET_BLAST_RADIUS
+            && projectile.source != player.id
+        {
+            hit_player(player, projectile, hits);
+        }
+    });
+}
+
+/// Deals the damage of the projectile to the player. The shield of the player absorbs damage
+/// first.
+fn hit_player(
+    player: &mut Player,
+    projectile: &mut Projectile,
+    hits: &mut Vec<(Uuid, i32, bool, bool)>,
+) {
+    let health_before = player.health;
+    let absorbed_damage = std::cmp::min(player.shield, projectile.damage);
+    player.shield -= absorbed_damage;
+    player.health = std::cmp::max(0, player.health - (projectile.damage - absorbed_damage));
+
+    let damage = (health_before - player.health) as i32;
+    player.stats.damage_taken += damage;
+
+    hits.push((
+        projectile.source,
+        damage,
+        health_before > 0 && player.health == 0,
+        projectile.hit_players.is_empty(),
+    ));
+    projectile.hit_players.push(player.id);
+
+    player.damage_inflicted_by.push(projectile.source);
+}
+
+/// Start and end of the path the projectile flies during this tick and the length of the path.
+fn get_path_of_projectile(projectile: &Projectile) -> ((f64, f64), (f64, f64), f64) {
+    let distance = match projectile.remaining_range {
+        Some(range) => range.min(projectile.travel_distance),
+        None => projectile.travel_distance,
+    };
+
+    let end = get_ending_coordinates_of_projectile(
+        projectile.x,
+        projectile.y,
+        projectile.direction,
+        distance,
+    );
+
+    return ((projectile.x, projectile.y), end, distance);
+}
+
+/// Time at which the projectile enters the first obstacle on its path, together with the field of
+/// the obstacle.
+fn get_obstacle_hit(
+    obstacles: &[Obstacle],
+    start: (f64, f64),
+    end: (f64, f64),
+) -> Option<(f64, (i32, i32))> {
+    return obstacles
+        .iter()
+        .filter_map(|obstacle| {
+            let field = (obstacle.x.into(), obstacle.y.into());
+            collision::get_hit_time(start, end, field, field)
+                .map(|time| (time, (obstacle.x, obstacle.y)))
+        })
+        .min_by(|first, second| first.0.total_cmp(&second.0));
+}
+
+/// Times at which the projectile hits the living players on its path. Players move from their
+/// position at the start of the tick to their current position while the projectile flies,
+/// projectiles fired this tick start at the shooter.
+fn get_player_hit_times(
+    players: &IndexMap<Uuid, Player>,
+    start_positions: &HashMap<Uuid, (i32, i32)>,
+    projectile: &Projectile,
+    start: (f64, f64),
+    end: (f64, f64),
+) -> Vec<(Uuid, f64)> {
+    return players
+        .values()
+        .filter(|player| {
+            player.health > 0
+                && player.id != projectile.source
+                && !projectile.hit_players.contains(&player.id)
+        })
+        .filter_map(|player| {
+            let field_start = start_positions
+                .get(&player.id)
+                .cloned()
+                .unwrap_or((player.x, player.y));
+
+            collision::get_hit_time(
+                start,
+                end,
+                (field_start.0.into(), field_start.1.into()),
+                (player.x.into(), player.y.into()),
+            )
+            .map(|time| (player.id, time))
+        })
+        .collect();
+}
+
+/// Projectiles of different players, which pass the same field during this tick, destroy each
+/// other. Only fields both projectiles reach before they are stopped by an obstacle or a player
+/// count. Returns the time at which every destroyed projectile enters the first field it shares
+/// with another projectile, together with the field.
+fn get_annihilations(
+    game_state: &GameState,
+    start_positions: &HashMap<Uuid, (i32, i32)>,
+) -> HashMap<Uuid, (f64, (i32, i32))> {
+    let projectiles: Vec<&Projectile> = game_state.projectiles().collect();
+
+    let passed_fields: Vec<Vec<((i32, i32), f64)>> = projectiles
+        .iter()
+        .map(|projectile| {
+            let (start, end, _) = get_path_of_projectile(projectile);
+
+            let stop_time = get_obstacle_hit(&game_state.obstacles, start, end)
+                .map(|(time, _)| time)
+                .into_iter()
+                .chain(
+                    get_player_hit_times(
+                        &game_state.players,
+                        start_positions,
+                        projectile,
+                        start,
+                        end,
+                    )
+                    .into_iter()
+                    .map(|(_, time)| time),
+                )
+                .fold(f64::INFINITY, f64::min);
+
+            return get_fields_passed(start, end)
+                .into_iter()
+                .filter(|(_, time)| *time < stop_time)
+                .collect();
+        })
+        .collect();
+
+    let mut annihilations: HashMap<Uuid, (f64, (i32, i32))> = HashMap::new();
+    let mut annihilate = |projectile: &Projectile, time: f64, field: (i32, i32)| {
+        let annihilation = annihilations.entry(projectile.id).or_insert((time, field));
+
+        if time < annihilation.0 {
+            *annihilation = (time, field);
+        }
+    };
+
+    for first in 0..projectiles.len() {
+        for second in (first + 1)..projectiles.len() {
+            if projectiles[first].source == projectiles[second].source {
+                continue;
+            }
+
+            for (field, first_time) in &passed_fields[first] {
+                if let Some((_, second_time)) = passed_fields[second]
+                    .iter()
+                    .find(|(other_field, _)| other_field == field)
+                {
+                    annihilate(projectiles[first], *first_time, *field);
+                    annihilate(projectiles[second], *second_time, *field);
+                }
+            }
+        }
+    }
+
+    return annihilations;
+}
+
+/// Fields a projectile flying from `start` to `end` passes during a tick, together with the time
+/// it enters them.
+fn get_fields_passed(start: (f64, f64), end: (f64, f64)) -> Vec<((i32, i32), f64)> {
+    let mut fields: Vec<((i32, i32), f64)> = vec![];
+
+    for x in (start.0.min(end.0).round() as i32)..=(start.0.max(end.0).round() as i32) {
+        for y in (start.1.min(end.1).round() as i32)..=(start.1.max(end.1).round() as i32) {
+            let field = (x.into(), y.into());
+
+            if let Some(time) = collision::get_hit_time(start, end, field, field) {
+                fields.push(((x, y), time));
+            }
+        }
+    }
+
+    return fields;
+}
+
+fn is_obstacle(obstacles: &[Obstacle], x: i32, y: i32) -> bool {
+    return obstacles
+        .iter()
+        .any(|obstacle| obstacle.x == x && obstacle.y == y);
+}
+
+fn get_ending_coordinates_of_projectile(
+    start_x: f64,
+    start_y: f64,
+    direction: i32,
+    travel_distance: f64,
+) -> (f64, f64) {
+    let directional_vector = get_directional_vector_from_degrees(direction);
+
+    let end_x = start_x + travel_distance * directional_vector.0;
+    let end_y = start_y + travel_distance * directional_vector.1;
+
+    return (end_x, end_y);
+}
+
+fn get_directional_vector_from_degrees(degrees: i32) -> (f64, f64) {
+    let degrees_f64: f64 = degrees.into();
+
+    let radians = ((90.0 - degrees_f64) * PI) / 180.0;
+
+    return (f64::cos(radians), f64::sin(radians));
+}
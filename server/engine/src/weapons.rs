@@ -0,0 +1,71 @@
+use crate::config::GameConfig;
+use crate::models::{ProjectileType, WeaponType};
+
+/// Angles relative to the rotation of the player, at which the pellets of a shotgun are fired.
+const SHOTGUN_PELLET_ANGLES: [i32; 5] = [-20, -10, 0, 10, 20];
+/// Range of the shotgun pellets relative to the projectile speed.
+const SHOTGUN_RANGE_FACTOR: f64 = 1.5;
+/// Rockets fly at half the projectile speed.
+const ROCKET_SPEED_FACTOR: f64 = 0.5;
+/// Maximum distance in fields (horizontally, vertically or diagonally) of players damaged by the
+/// explosion of a rocket.
+pub const ROCKET_BLAST_RADIUS: i32 = 1;
+
+/// Behavior of the projectiles fired with a weapon. Speeds and damages are derived from
+/// `projectile_speed` and `damage_per_hit` of the configuration, so lobbies can tune all weapons
+/// at once.
+pub struct WeaponStats {
+    pub projectile_type: ProjectileType,
+    /// Angles relative to the rotation of the player, one projectile is fired per angle.
+    pub angles: &'static [i32],
+    pub speed: f64,
+    /// Distance after which a projectile disappears. `None` if it flies until it leaves the field.
+    pub range: Option<f64>,
+    pub damage: i16,
+    /// Ammo used per shot.
+    pub ammo_cost: i32,
+}
+
+pub fn get_weapon_stats(weapon: WeaponType, config: &GameConfig) -> WeaponStats {
+    return match weapon {
+        WeaponType::RIFLE => WeaponStats {
+            projectile_type: ProjectileType::BULLET,
+            angles: &[0],
+            speed: config.projectile_speed,
+            range: None,
+            damage: config.damage_per_hit,
+            ammo_cost: 1,
+        },
+        WeaponType::SHOTGUN => WeaponStats {
+            projectile_type: ProjectileType::PELLET,
+            angles: &SHOTGUN_PELLET_ANGLES,
+            speed: config.projectile_speed,
+            range: Some(config.projectile_speed * SHOTGUN_RANGE_FACTOR),
+            damage: config.damage_per_hit / 2,
+            ammo_cost: 1,
+        },
+        WeaponType::ROCKET => WeaponStats {
+            projectile_type: ProjectileType::ROCKET,
+            angles: &[0],
+            speed: config.projectile_speed * ROCKET_SPEED_FACTOR,
+            range: None,
+            damage: config.damage_per_hit.saturating_mul(3) / 2,
+            ammo_cost: 2,
+        },
+        // The beam crosses the whole field within the tick it is fired
+        WeaponType::LASER => {
+            let field_diagonal = (config.field_size_x as f64)
+                .hypot(config.field_size_y as f64)
+                .ceil();
+
+            WeaponStats {
+                projectile_type: ProjectileType::LASER,
+                angles: &[0],
+                speed: field_diagonal,
+                range: Some(field_diagonal),
+                damage: config.damage_per_hit.saturating_mul(3) / 4,
+                ammo_cost: 2,
+            }
+        }
+    };
+}
@@ -0,0 +1,103 @@
+use crate::config::{GameConfig, ZoneConfig};
+use crate::models::{Player, Zone, ZoneBounds, ZoneShape};
+use crate::GameState;
+
+/// The zone at the start of a game, which covers every field.
+pub fn create_zone(config: &GameConfig, zone_config: &ZoneConfig) -> Zone {
+    let center_x = (config.field_size_x - 1) as f64 / 2.0;
+    let center_y = (config.field_size_y - 1) as f64 / 2.0;
+
+    let current = match zone_config.shape {
+        ZoneShape::RECTANGLE => ZoneBounds {
+            center_x,
+            center_y,
+            radius_x: center_x,
+            radius_y: center_y,
+        },
+        ZoneShape::CIRCLE => {
+            let radius = center_x.hypot(center_y);
+
+            ZoneBounds {
+                center_x,
+                center_y,
+                radius_x: radius,
+                radius_y: radius,
+            }
+        }
+    };
+
+    return Zone {
+        shape: zone_config.shape,
+        current,
+        next: shrink(&current, zone_config),
+        ticks_until_shrink: Some(zone_config.shrink_start_ticks),
+    };
+}
+
+/// Counts down the ticks until the next shrink and shrinks the zone once they are over.
+pub fn update_zone(zone: &mut Zone, zone_config: &ZoneConfig) {
+    let ticks_until_shrink = match zone.ticks_until_shrink {
+        Some(ticks_until_shrink) => ticks_until_shrink - 1,
+        None => return,
+    };
+
+    if ticks_until_shrink > 0 {
+        zone.ticks_until_shrink = Some(ticks_until_shrink);
+        return;
+    }
+
+    zone.current = zone.next;
+    zone.next = shrink(&zone.current, zone_config);
+    zone.ticks_until_shrink = if zone.next == zone.current {
+        None
+    } else {
+        Some(zone_config.shrink_interval_ticks)
+    };
+}
+
+/// Living players outside of the zone lose `damage_per_tick` health.
+pub fn apply_zone_damage(game_state: &mut GameState, zone_config: &ZoneConfig) {
+    let zone = match &game_state.zone {
+        Some(zone) => zone,
+        None => return,
+    };
+
+    for player in game_state.players.values_mut() {
+        if player.health <= 0 || is_within_zone(zone, player) {
+            continue;
+        }
+
+        let health_before = player.health;
+        player.health = std::cmp::max(0, player.health - zone_config.damage_per_tick);
+        player.stats.damage_taken += (health_before - player.health) as i32;
+    }
+}
+
+fn is_within_zone(zone: &Zone, player: &Player) -> bool {
+    let distance_x = (player.x as f64 - zone.current.center_x).abs();
+    let distance_y = (player.y as f64 - zone.current.center_y).abs();
+
+    return match zone.shape {
+        ZoneShape::RECTANGLE => {
+            distance_x <= zone.current.radius_x && distance_y <= zone.current.radius_y
+        }
+        ZoneShape::CIRCLE => distance_x.hypot(distance_y) <= zone.current.radius_x,
+    };
+}
+
+fn shrink(bounds: &ZoneBounds, zone_config: &ZoneConfig) -> ZoneBounds {
+    let shrink_radius = |radius: f64| {
+        if radius <= zone_config.min_radius {
+            return radius;
+        }
+
+        return (radius * zone_config.shrink_factor).max(zone_config.min_radius);
+    };
+
+    return ZoneBounds {
+        center_x: bounds.center_x,
+        center_y: bounds.center_y,
+        radius_x: shrink_radius(bounds.radius_x),
+        radius_y: shrink_radius(bounds.radius_y),
+    };
+}
@@ -0,0 +1,136 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::{action, player, spawn, start_game_with_config};
+use mca_hackathon_engine::{ClientAction, GameConfig, GameEngine, Player};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Creates a started game, in which the first player shoots to the right past the second player.
+fn start_duel(config: GameConfig) -> (GameEngine, Vec<Uuid>) {
+    return start_game_with_config(config, vec![spawn(5, 5, 90), spawn(5, 20, 0)], vec![]);
+}
+
+fn act(engine: &mut GameEngine, player_id: Uuid, client_action: ClientAction) -> &Player {
+    engine.apply_actions(&HashMap::from([(player_id, action(client_action))]));
+
+    return player(engine, &player_id);
+}
+
+#[test]
+fn shooting_without_ammo_is_refused() {
+    let config = GameConfig {
+        max_ammo: 2,
+        shot_cooldown_ticks: 0,
+        ..GameConfig::default()
+    };
+    let (mut engine, players) = start_duel(config);
+
+    assert_eq!(act(&mut engine, players[0], ClientAction::SHOOT).ammo, 1);
+    assert_eq!(act(&mut engine, players[0], ClientAction::SHOOT).ammo, 0);
+
+    let shooter = act(&mut engine, players[0], ClientAction::SHOOT);
+    assert!(!shooter.last_action_success);
+    assert_eq!(
+        shooter.error_message,
+        "Cannot SHOOT, because player has not enough ammo left for the RIFLE, RELOAD first"
+    );
+    assert_eq!(shooter.ammo, 0);
+    assert_eq!(shooter.stats.shots_fired, 2);
+    assert_eq!(engine.game_state().entities.len(), 2);
+}
+
+#[test]
+fn reload_refills_ammo_after_reload_ticks() {
+    let config = GameConfig {
+        max_ammo: 2,
+        reload_ticks: 3,
+        shot_cooldown_ticks: 0,
+        ..GameConfig::default()
+    };
+    let (mut engine, players) = start_duel(config);
+
+    act(&mut engine, players[0], ClientAction::SHOOT);
+    act(&mut engine, players[0], ClientAction::SHOOT);
+
+    // The tick of the action counts as the first tick of the reload
+    let reloading = act(&mut engine, players[0], ClientAction::RELOAD);
+    assert!(reloading.last_action_success);
+    assert_eq!(reloading.reload_ticks, 2);
+    assert_eq!(reloading.ammo, 0);
+
+    let reloading = act(&mut engine, players[0], ClientAction::SHOOT);
+    assert!(!reloading.last_action_success);
+    assert_eq!(
+        reloading.error_message,
+        "Cannot SHOOT, because player is reloading"
+    );
+
+    let reloaded = act(&mut engine, players[0], ClientAction::UP);
+    assert!(reloaded.last_action_success);
+    assert_eq!(reloaded.reload_ticks, 0);
+    assert_eq!(reloaded.ammo, 2);
+
+    assert!(act(&mut engine, players[0], ClientAction::SHOOT).last_action_success);
+}
+
+#[test]
+fn reload_with_full_ammo_is_refused() {
+    let (mut engine, players) = start_duel(GameConfig::default());
+
+    let player = act(&mut engine, players[0], ClientAction::RELOAD);
+
+    assert!(!player.last_action_success);
+    assert_eq!(
+        player.error_message,
+        "Cannot RELOAD, because ammo is already full"
+    );
+    assert_eq!(player.reload_ticks, 0);
+}
+
+#[test]
+fn shooting_during_cooldown_is_refused() {
+    let config = GameConfig {
+        shot_cooldown_ticks: 2,
+        ..GameConfig::default()
+    };
+    let (mut engine, players) = start_duel(config);
+
+    assert!(act(&mut engine, players[0], ClientAction::SHOOT).last_action_success);
+
+    let waiting = act(&mut engine, players[0], ClientAction::SHOOT);
+    assert!(!waiting.last_action_success);
+    assert_eq!(
+        waiting.error_message,
+        "Cannot SHOOT, because player has to wait 2 more tick(s) before shooting again"
+    );
+
+    let waiting = act(&mut engine, players[0], ClientAction::SHOOT);
+    assert_eq!(
+        waiting.error_message,
+        "Cannot SHOOT, because player has to wait 1 more tick(s) before shooting again"
+    );
+
+    let shooter = act(&mut engine, players[0], ClientAction::SHOOT);
+    assert!(shooter.last_action_success);
+    assert_eq!(shooter.stats.shots_fired, 2);
+}
+
+#[test]
+fn ammo_regenerates_up_to_max_ammo() {
+    let config = GameConfig {
+        max_ammo: 3,
+        ammo_regeneration_ticks: 2,
+        ..GameConfig::default()
+    };
+    let (mut engine, players) = start_duel(config);
+
+    assert_eq!(act(&mut engine, players[0], ClientAction::SHOOT).ammo, 2);
+    assert_eq!(act(&mut engine, players[0], ClientAction::UP).ammo, 3);
+
+    engine.apply_actions(&HashMap::new());
+    engine.apply_actions(&HashMap::new());
+
+    assert_eq!(engine.player(&players[0]).unwrap().ammo, 3);
+}
@@ -0,0 +1,316 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::{
+    action, health, projectiles, spawn, start_game, start_game_with_config, switch_weapon,
+};
+use mca_hackathon_engine::collision::get_hit_time;
+use mca_hackathon_engine::{ClientAction, GameConfig, Obstacle, WeaponType};
+use std::collections::HashMap;
+
+#[test]
+fn projectile_enters_resting_field() {
+    let time = get_hit_time((0.0, 0.0), (6.0, 0.0), (3.0, 0.0), (3.0, 0.0));
+
+    assert_eq!(time, Some(2.5 / 6.0));
+}
+
+#[test]
+fn projectile_along_border_of_field_misses() {
+    let time = get_hit_time((0.0, 0.5), (6.0, 0.5), (3.0, 0.0), (3.0, 0.0));
+
+    assert_eq!(time, None);
+}
+
+#[test]
+fn projectile_through_corner_of_field_misses() {
+    let corner = get_hit_time((0.0, 0.0), (2.0, 2.0), (1.0, 0.0), (1.0, 0.0));
+    let diagonal = get_hit_time((0.0, 0.0), (2.0, 2.0), (1.0, 1.0), (1.0, 1.0));
+
+    assert_eq!(corner, None);
+    assert_eq!(diagonal, Some(0.25));
+}
+
+#[test]
+fn projectile_reaching_field_at_end_of_tick_misses() {
+    let time = get_hit_time((0.0, 0.0), (2.5, 0.0), (3.0, 0.0), (3.0, 0.0));
+
+    assert_eq!(time, None);
+}
+
+#[test]
+fn projectile_hits_field_crossing_its_path() {
+    // The field crosses the path at the same time as the projectile passes
+    let time = get_hit_time((0.0, 0.0), (4.0, 0.0), (2.0, 1.0), (2.0, -1.0));
+
+    assert!(time.is_some());
+}
+
+#[test]
+fn diagonal_shot_hits_players_on_the_diagonal_only() {
+    let (mut engine, players) = start_game(
+        vec![
+            spawn(5, 5, 45),
+            spawn(8, 8, 0),
+            spawn(6, 5, 0),
+            spawn(9, 8, 0),
+        ],
+        vec![],
+    );
+
+    engine.apply_actions(&HashMap::from([(players[0], action(ClientAction::SHOOT))]));
+
+    assert_eq!(health(&engine, &players[1]), 80);
+    assert_eq!(health(&engine, &players[2]), 100);
+    assert_eq!(health(&engine, &players[3]), 100);
+}
+
+#[test]
+fn player_leaving_the_path_too_late_is_hit() {
+    // The projectile flies from (7, 10) to (13, 10) during the tick
+    let (mut engine, players) = start_game(
+        vec![spawn(7, 10, 90), spawn(10, 10, 0), spawn(12, 10, 0)],
+        vec![],
+    );
+
+    engine.apply_actions(&HashMap::from([
+        (players[0], action(ClientAction::SHOOT)),
+        (players[1], action(ClientAction::UP)),
+        (players[2], action(ClientAction::UP)),
+    ]));
+
+    assert_eq!(health(&engine, &players[1]), 80);
+    assert_eq!(health(&engine, &players[2]), 100);
+}
+
+#[test]
+fn player_entering_the_path_is_hit_only_before_the_projectile_passed() {
+    // The projectile flies from (7, 10) to (13, 10) during the tick
+    let (mut engine, players) = start_game(
+        vec![spawn(7, 10, 90), spawn(8, 11, 0), spawn(12, 11, 0)],
+        vec![],
+    );
+
+    engine.apply_actions(&HashMap::from([
+        (players[0], action(ClientAction::SHOOT)),
+        (players[1], action(ClientAction::DOWN)),
+        (players[2], action(ClientAction::DOWN)),
+    ]));
+
+    assert_eq!(health(&engine, &players[1]), 100);
+    assert_eq!(health(&engine, &players[2]), 80);
+}
+
+#[test]
+fn obstacle_stops_projectile_before_players_behind_it() {
+    let (mut engine, players) = start_game(
+        vec![spawn(5, 10, 90), spawn(7, 10, 0), spawn(10, 10, 0)],
+        vec![Obstacle { x: 8, y: 10 }],
+    );
+
+    engine.apply_actions(&HashMap::from([(players[0], action(ClientAction::SHOOT))]));
+
+    assert_eq!(health(&engine, &players[1]), 80);
+    assert_eq!(health(&engine, &players[2]), 100);
+    assert!(engine.game_state().entities.is_empty());
+}
+
+#[test]
+fn projectile_hits_resting_player_at_end_of_its_path_only_once() {
+    // The projectile ends the first tick on the field of the player and starts the next tick there
+    let (mut engine, players) = start_game(vec![spawn(4, 10, 90), spawn(10, 10, 0)], vec![]);
+
+    engine.apply_actions(&HashMap::from([(players[0], action(ClientAction::SHOOT))]));
+    engine.apply_actions(&HashMap::new());
+
+    assert_eq!(health(&engine, &players[1]), 80);
+    assert_eq!(engine.player(&players[1]).unwrap().stats.damage_taken, 20);
+}
+
+#[test]
+fn projectile_stops_at_first_player_it_hits() {
+    let (mut engine, players) = start_game(
+        vec![spawn(5, 10, 90), spawn(7, 10, 0), spawn(9, 10, 0)],
+        vec![],
+    );
+
+    engine.apply_actions(&HashMap::from([(players[0], action(ClientAction::SHOOT))]));
+
+    assert_eq!(health(&engine, &players[1]), 80);
+    assert_eq!(health(&engine, &players[2]), 100);
+    assert!(engine.game_state().entities.is_empty());
+}
+
+#[test]
+fn projectile_passes_dead_players() {
+    let config = GameConfig {
+        starting_health: 20,
+        shot_cooldown_ticks: 0,
+        ..GameConfig::default()
+    };
+    let (mut engine, players) = start_game_with_config(
+        config,
+        vec![spawn(5, 10, 90), spawn(7, 10, 0), spawn(9, 10, 0)],
+        vec![],
+    );
+
+    engine.apply_actions(&HashMap::from([(players[0], action(ClientAction::SHOOT))]));
+    assert_eq!(health(&engine, &players[1]), 0);
+
+    engine.apply_actions(&HashMap::from([(players[0], action(ClientAction::SHOOT))]));
+
+    assert_eq!(health(&engine, &players[2]), 0);
+    assert_eq!(engine.player(&players[1]).unwrap().stats.damage_taken, 20);
+    assert_eq!(engine.player(&players[0]).unwrap().stats.shots_hit, 2);
+}
+
+#[test]
+fn projectile_disappears_after_max_range() {
+    let config = GameConfig {
+        projectile_max_range: Some(8.0),
+        ..GameConfig::default()
+    };
+    // The projectile flies 6 units in the first tick and 2 units in the second tick
+    let (mut engine, players) = start_game_with_config(
+        config,
+        vec![spawn(2, 10, 90), spawn(10, 10, 0), spawn(12, 10, 0)],
+        vec![],
+    );
+
+    engine.apply_actions(&HashMap::from([(players[0], action(ClientAction::SHOOT))]));
+    assert_eq!(projectiles(&engine)[0].remaining_range, Some(2.0));
+
+    engine.apply_actions(&HashMap::new());
+    engine.apply_actions(&HashMap::new());
+
+    assert_eq!(health(&engine, &players[1]), 80);
+    assert!(engine.game_state().entities.is_empty());
+
+    engine.apply_actions(&HashMap::from([
+        (players[0], action(ClientAction::LEFT)),
+        (players[1], action(ClientAction::UP)),
+    ]));
+    engine.apply_actions(&HashMap::from([(players[0], action(ClientAction::SHOOT))]));
+    engine.apply_actions(&HashMap::new());
+
+    assert_eq!(health(&engine, &players[2]), 100);
+}
+
+#[test]
+fn projectile_disappears_after_max_lifetime() {
+    let config = GameConfig {
+        projectile_max_lifetime_ticks: Some(1),
+        ..GameConfig::default()
+    };
+    let (mut engine, players) =
+        start_game_with_config(config, vec![spawn(2, 10, 90), spawn(10, 10, 0)], vec![]);
+
+    engine.apply_actions(&HashMap::from([(players[0], action(ClientAction::SHOOT))]));
+    assert_eq!(projectiles(&engine)[0].remaining_ticks, Some(0));
+
+    engine.apply_actions(&HashMap::new());
+
+    assert_eq!(health(&engine, &players[1]), 100);
+    assert!(engine.game_state().entities.is_empty());
+}
+
+#[test]
+fn rocket_explosion_skips_dead_players() {
+    let config = GameConfig {
+        starting_health: 20,
+        shot_cooldown_ticks: 0,
+        ..GameConfig::default()
+    };
+    // Both rockets explode next to the same players, the second one only after they are dead
+    let (mut engine, players) = start_game_with_config(
+        config,
+        vec![spawn(5, 10, 90), spawn(8, 10, 0), spawn(8, 11, 0)],
+        vec![Obstacle { x: 9, y: 10 }],
+    );
+
+    engine.apply_actions(&HashMap::from([(
+        players[0],
+        switch_weapon(WeaponType::ROCKET),
+    )]));
+    engine.apply_actions(&HashMap::from([(players[0], action(ClientAction::SHOOT))]));
+    assert_eq!(health(&engine, &players[1]), 0);
+    assert_eq!(health(&engine, &players[2]), 0);
+
+    engine.apply_actions(&HashMap::from([(players[0], action(ClientAction::SHOOT))]));
+    engine.apply_actions(&HashMap::new());
+
+    let shooter = engine.player(&players[0]).unwrap();
+    assert!(engine.game_state().entities.is_empty());
+    assert_eq!(shooter.stats.shots_hit, 1);
+    assert_eq!(shooter.stats.kills, 2);
+    assert_eq!(engine.player(&players[2]).unwrap().stats.damage_taken, 20);
+}
+
+#[test]
+fn rocket_explodes_when_reaching_its_max_lifetime() {
+    let config = GameConfig {
+        projectile_max_lifetime_ticks: Some(1),
+        ..GameConfig::default()
+    };
+    // The rocket flies from (5, 10) to (8, 10) and stops next to the second player
+    let (mut engine, players) =
+        start_game_with_config(config, vec![spawn(5, 10, 90), spawn(9, 10, 0)], vec![]);
+
+    engine.apply_actions(&HashMap::from([(
+        players[0],
+        switch_weapon(WeaponType::ROCKET),
+    )]));
+    engine.apply_actions(&HashMap::from([(players[0], action(ClientAction::SHOOT))]));
+
+    assert_eq!(health(&engine, &players[1]), 70);
+    assert!(engine.game_state().entities.is_empty());
+}
+
+#[test]
+fn crossing_projectiles_annihilate_each_other_if_enabled() {
+    let spawns = vec![spawn(5, 10, 90), spawn(15, 10, 270)];
+
+    for (annihilation, expected_health) in [(false, 80), (true, 100)] {
+        let config = GameConfig {
+            projectile_annihilation: annihilation,
+            ..GameConfig::default()
+        };
+        let (mut engine, players) = start_game_with_config(config, spawns.clone(), vec![]);
+
+        // Both projectiles pass (10, 10), the second player moves out of the way of the first one
+        engine.apply_actions(&HashMap::from([
+            (players[0], action(ClientAction::SHOOT)),
+            (players[1], action(ClientAction::SHOOT)),
+        ]));
+        engine.apply_actions(&HashMap::from([(players[1], action(ClientAction::UP))]));
+        engine.apply_actions(&HashMap::new());
+
+        assert_eq!(health(&engine, &players[0]), expected_health);
+        assert_eq!(engine.game_state().entities.is_empty(), annihilation);
+    }
+}
+
+#[test]
+fn projectile_stopped_by_obstacle_does_not_annihilate_behind_it() {
+    let config = GameConfig {
+        projectile_annihilation: true,
+        ..GameConfig::default()
+    };
+    // The paths cross at (6, 6), which the first projectile never reaches
+    let (mut engine, players) = start_game_with_config(
+        config,
+        vec![spawn(2, 6, 90), spawn(6, 1, 0)],
+        vec![Obstacle { x: 4, y: 6 }],
+    );
+
+    engine.apply_actions(&HashMap::from([
+        (players[0], action(ClientAction::SHOOT)),
+        (players[1], action(ClientAction::SHOOT)),
+    ]));
+
+    let projectiles = projectiles(&engine);
+    assert_eq!(projectiles.len(), 1);
+    assert_eq!(projectiles[0].source, players[1]);
+    assert_eq!((projectiles[0].x, projectiles[0].y), (6.0, 7.0));
+}
@@ -0,0 +1,85 @@
+#![allow(clippy::needless_return, dead_code)]
+
+use mca_hackathon_engine::{
+    ClientAction, GameConfig, GameEngine, Obstacle, Pickup, PickupLocation, Player, PlayerAction,
+    Projectile, Spawn, WeaponType,
+};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Creates a started game with one player per spawn, the players are returned in spawn order.
+pub fn start_game(spawns: Vec<Spawn>, obstacles: Vec<Obstacle>) -> (GameEngine, Vec<Uuid>) {
+    return start_game_with_config(GameConfig::default(), spawns, obstacles);
+}
+
+pub fn start_game_with_config(
+    config: GameConfig,
+    spawns: Vec<Spawn>,
+    obstacles: Vec<Obstacle>,
+) -> (GameEngine, Vec<Uuid>) {
+    return start_game_on_map(config, spawns, obstacles, vec![]);
+}
+
+/// Creates a started game on a map with the given obstacles and pickup locations. The seed of the
+/// configuration is kept if it is set.
+pub fn start_game_on_map(
+    config: GameConfig,
+    spawns: Vec<Spawn>,
+    obstacles: Vec<Obstacle>,
+    pickups: Vec<PickupLocation>,
+) -> (GameEngine, Vec<Uuid>) {
+    let config = GameConfig {
+        seed: config.seed.or(Some(0)),
+        max_players: spawns.len(),
+        spawns: BTreeMap::from([(spawns.len(), spawns.clone())]),
+        obstacles,
+        pickups,
+        ..config
+    };
+
+    let mut engine = GameEngine::new(config);
+    let player_ids = spawns
+        .iter()
+        .enumerate()
+        .map(|(index, _)| engine.add_player(format!("player-{}", index)).unwrap())
+        .collect();
+    engine.start();
+
+    return (engine, player_ids);
+}
+
+pub fn spawn(x: i32, y: i32, rotation: i32) -> Spawn {
+    return Spawn { x, y, rotation };
+}
+
+pub fn action(action: ClientAction) -> PlayerAction {
+    return PlayerAction {
+        action,
+        degrees: None,
+        weapon: None,
+    };
+}
+
+pub fn switch_weapon(weapon: WeaponType) -> PlayerAction {
+    return PlayerAction {
+        action: ClientAction::SWITCH_WEAPON,
+        degrees: None,
+        weapon: Some(weapon),
+    };
+}
+
+pub fn player<'a>(engine: &'a GameEngine, player_id: &Uuid) -> &'a Player {
+    return engine.player(player_id).unwrap();
+}
+
+pub fn health(engine: &GameEngine, player_id: &Uuid) -> i16 {
+    return player(engine, player_id).health;
+}
+
+pub fn projectiles(engine: &GameEngine) -> Vec<&Projectile> {
+    return engine.game_state().projectiles().collect();
+}
+
+pub fn pickups(engine: &GameEngine) -> Vec<&Pickup> {
+    return engine.game_state().pickups().collect();
+}
@@ -0,0 +1,52 @@
+#![allow(clippy::needless_return)]
+
+use mca_hackathon_engine::{deserialize_config, GameConfig, GameEngine, Spawn};
+
+fn parse_config(json: &str) -> GameConfig {
+    return deserialize_config(&mut serde_json::Deserializer::from_str(json)).unwrap();
+}
+
+#[test]
+fn custom_field_size_without_spawns_uses_ring_formation() {
+    let config = parse_config(r#"{"field_size_x": 20, "field_size_y": 20}"#);
+
+    assert!(config.spawns.is_empty());
+    assert_eq!(config.validate(), Ok(()));
+
+    let max_players = config.max_players;
+    let mut engine = GameEngine::new(config);
+
+    for index in 0..max_players {
+        engine.add_player(format!("player-{}", index)).unwrap();
+    }
+    engine.start();
+
+    assert!(engine
+        .game_state()
+        .players
+        .values()
+        .all(|player| (0..20).contains(&player.x) && (0..20).contains(&player.y)));
+}
+
+#[test]
+fn custom_field_size_keeps_configured_spawns() {
+    let config = parse_config(
+        r#"{"field_size_x": 20, "field_size_y": 20, "spawns": {"1": [{"x": 3, "y": 4, "rotation": 0}]}}"#,
+    );
+
+    assert_eq!(
+        config.spawns[&1],
+        vec![Spawn {
+            x: 3,
+            y: 4,
+            rotation: 0
+        }]
+    );
+}
+
+#[test]
+fn default_field_size_keeps_default_spawns() {
+    let config = parse_config(r#"{"max_rounds": 100}"#);
+
+    assert_eq!(config.spawns, GameConfig::default().spawns);
+}
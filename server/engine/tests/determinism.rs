@@ -0,0 +1,65 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::{action, spawn, start_game_on_map};
+use mca_hackathon_engine::{ClientAction, GameConfig, Obstacle, PickupLocation, PlayerAction};
+use std::collections::HashMap;
+
+/// Plays a game with the given seed and returns the serialized game state after every tick.
+fn play(seed: u64) -> Vec<String> {
+    let config = GameConfig {
+        seed: Some(seed),
+        pickup_spawn_interval_ticks: 2,
+        ..GameConfig::default()
+    };
+    let (mut engine, players) = start_game_on_map(
+        config,
+        vec![spawn(5, 5, 0), spawn(20, 5, 180), spawn(12, 20, 270)],
+        vec![Obstacle { x: 12, y: 5 }, Obstacle { x: 13, y: 12 }],
+        vec![
+            PickupLocation { x: 3, y: 3 },
+            PickupLocation { x: 10, y: 10 },
+            PickupLocation { x: 25, y: 25 },
+            PickupLocation { x: 8, y: 18 },
+        ],
+    );
+    let rounds: Vec<[ClientAction; 3]> = vec![
+        [
+            ClientAction::SHOOT,
+            ClientAction::SHOOT,
+            ClientAction::SHOOT,
+        ],
+        [ClientAction::DOWN, ClientAction::LEFT, ClientAction::UP],
+        [ClientAction::RIGHT, ClientAction::SHOOT, ClientAction::TURN],
+        [ClientAction::SHOOT, ClientAction::DOWN, ClientAction::SHOOT],
+        [
+            ClientAction::RELOAD,
+            ClientAction::SHOOT,
+            ClientAction::LEFT,
+        ],
+    ];
+
+    let mut states = vec![serde_json::to_string(&engine.game_state_out(0)).unwrap()];
+    for round in rounds.iter().cycle().take(20) {
+        let actions: HashMap<_, PlayerAction> = players
+            .iter()
+            .zip(round)
+            .map(|(player_id, client_action)| (*player_id, action(client_action.clone())))
+            .collect();
+        engine.apply_actions(&actions);
+        states.push(serde_json::to_string(&engine.game_state_out(0)).unwrap());
+    }
+
+    return states;
+}
+
+#[test]
+fn same_seed_and_actions_produce_same_game() {
+    assert_eq!(play(42), play(42));
+}
+
+#[test]
+fn different_seeds_produce_different_games() {
+    assert_ne!(play(42), play(43));
+}
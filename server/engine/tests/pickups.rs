@@ -0,0 +1,199 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::{action, pickups, player, spawn, start_game_on_map};
+use mca_hackathon_engine::{ClientAction, GameConfig, PickupLocation, PickupType};
+use std::collections::HashMap;
+
+#[test]
+fn pickup_appears_every_interval_on_free_location() {
+    let config = GameConfig {
+        seed: Some(0),
+        pickup_spawn_interval_ticks: 3,
+        ..GameConfig::default()
+    };
+    let (mut engine, _) = start_game_on_map(
+        config,
+        vec![spawn(5, 5, 0), spawn(20, 20, 0)],
+        vec![],
+        vec![PickupLocation { x: 10, y: 10 }],
+    );
+
+    engine.apply_actions(&HashMap::new());
+    engine.apply_actions(&HashMap::new());
+    assert!(pickups(&engine).is_empty());
+
+    engine.apply_actions(&HashMap::new());
+    let spawned = pickups(&engine);
+    assert_eq!(spawned.len(), 1);
+    assert_eq!((spawned[0].x, spawned[0].y), (10, 10));
+
+    // The only location is occupied by the first pickup
+    engine.apply_actions(&HashMap::new());
+    engine.apply_actions(&HashMap::new());
+    engine.apply_actions(&HashMap::new());
+    assert_eq!(pickups(&engine).len(), 1);
+}
+
+#[test]
+fn pickup_does_not_appear_below_player() {
+    let config = GameConfig {
+        seed: Some(0),
+        pickup_spawn_interval_ticks: 1,
+        ..GameConfig::default()
+    };
+    let (mut engine, _) = start_game_on_map(
+        config,
+        vec![spawn(5, 5, 0), spawn(20, 20, 0)],
+        vec![],
+        vec![PickupLocation { x: 5, y: 5 }],
+    );
+
+    engine.apply_actions(&HashMap::new());
+    engine.apply_actions(&HashMap::new());
+
+    assert!(pickups(&engine).is_empty());
+}
+
+#[test]
+fn collected_pickups_apply_their_effect() {
+    let mut collected_types = vec![];
+
+    // The type of a pickup is random, so games with different seeds are played until every type
+    // was collected
+    for seed in 0..100 {
+        let config = GameConfig {
+            seed: Some(seed),
+            pickup_spawn_interval_ticks: 1,
+            ..GameConfig::default()
+        };
+        // The second player damages the first one, so health pickups have an effect as well
+        let (mut engine, players) = start_game_on_map(
+            config,
+            vec![spawn(5, 5, 0), spawn(5, 2, 0)],
+            vec![],
+            vec![PickupLocation { x: 6, y: 5 }],
+        );
+
+        engine.apply_actions(&HashMap::from([(players[1], action(ClientAction::SHOOT))]));
+        assert_eq!(player(&engine, &players[0]).health, 80);
+
+        let pickup_type = pickups(&engine)[0].pickup_type;
+
+        engine.apply_actions(&HashMap::from([(players[0], action(ClientAction::RIGHT))]));
+
+        let collector = player(&engine, &players[0]);
+        assert!(pickups(&engine).is_empty());
+
+        match pickup_type {
+            PickupType::HEALTH => assert_eq!(collector.health, 100),
+            PickupType::SHIELD => assert_eq!(collector.shield, 40),
+            PickupType::SPEED_BOOST => assert_eq!(collector.speed_boost_ticks, 20),
+            PickupType::DAMAGE_BOOST => assert_eq!(collector.damage_boost_ticks, 20),
+        }
+
+        if !collected_types.contains(&pickup_type) {
+            collected_types.push(pickup_type);
+        }
+
+        if collected_types.len() == 4 {
+            return;
+        }
+    }
+
+    panic!("Only collected {:?}", collected_types);
+}
+
+#[test]
+fn first_joined_player_collects_contested_pickup() {
+    let config = GameConfig {
+        seed: Some(0),
+        pickup_spawn_interval_ticks: 1,
+        ..GameConfig::default()
+    };
+    let (mut engine, players) = start_game_on_map(
+        config,
+        vec![spawn(4, 5, 0), spawn(6, 5, 0)],
+        vec![],
+        vec![PickupLocation { x: 5, y: 5 }],
+    );
+
+    engine.apply_actions(&HashMap::new());
+    assert_eq!(pickups(&engine).len(), 1);
+
+    engine.apply_actions(&HashMap::from([
+        (players[0], action(ClientAction::RIGHT)),
+        (players[1], action(ClientAction::LEFT)),
+    ]));
+
+    // Both players stand on the location, so no new pickup appears
+    assert!(pickups(&engine).is_empty());
+
+    let second = player(&engine, &players[1]);
+    assert_eq!(second.health, 100);
+    assert_eq!(second.shield, 0);
+    assert_eq!(second.speed_boost_ticks, 0);
+    assert_eq!(second.damage_boost_ticks, 0);
+}
+
+#[test]
+fn speed_boost_moves_player_two_fields() {
+    for seed in 0..100 {
+        let config = GameConfig {
+            seed: Some(seed),
+            pickup_spawn_interval_ticks: 1,
+            ..GameConfig::default()
+        };
+        let (mut engine, players) = start_game_on_map(
+            config,
+            vec![spawn(5, 5, 0), spawn(20, 20, 0)],
+            vec![],
+            vec![PickupLocation { x: 5, y: 6 }],
+        );
+
+        engine.apply_actions(&HashMap::new());
+
+        if pickups(&engine)[0].pickup_type != PickupType::SPEED_BOOST {
+            continue;
+        }
+
+        engine.apply_actions(&HashMap::from([(players[0], action(ClientAction::UP))]));
+        engine.apply_actions(&HashMap::from([(players[0], action(ClientAction::UP))]));
+
+        let boosted = player(&engine, &players[0]);
+        assert_eq!((boosted.x, boosted.y), (5, 8));
+        assert_eq!(boosted.speed_boost_ticks, 19);
+        return;
+    }
+
+    panic!("No speed boost appeared");
+}
+
+#[test]
+fn pickups_are_sent_as_entities() {
+    let config = GameConfig {
+        seed: Some(0),
+        pickup_spawn_interval_ticks: 1,
+        ..GameConfig::default()
+    };
+    let (mut engine, players) = start_game_on_map(
+        config,
+        vec![spawn(5, 5, 90), spawn(20, 20, 0)],
+        vec![],
+        vec![PickupLocation { x: 10, y: 10 }],
+    );
+
+    engine.apply_actions(&HashMap::from([(players[0], action(ClientAction::SHOOT))]));
+
+    let game_state_out = serde_json::to_value(engine.game_state_out(0)).unwrap();
+    let entity_types: Vec<&str> = game_state_out["entities"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entity| entity["entity_type"].as_str().unwrap())
+        .collect();
+
+    assert_eq!(entity_types, vec!["PROJECTILE", "PICKUP"]);
+    assert!(game_state_out.get("pickups").is_none());
+}
@@ -0,0 +1,41 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::{spawn, start_game};
+use mca_hackathon_engine::FinishReason;
+use std::collections::HashMap;
+
+#[test]
+fn eliminated_player_stays_in_standings() {
+    let (mut engine, players) = start_game(
+        vec![spawn(5, 5, 0), spawn(10, 10, 0), spawn(15, 15, 0)],
+        vec![],
+    );
+
+    engine.apply_actions(&HashMap::new());
+    engine.eliminate_player(&players[1]);
+    engine.advance_tick();
+
+    let standings = engine.standings();
+    assert_eq!(standings.len(), 3);
+    assert_eq!(standings[2].player_id, players[1]);
+    assert_eq!(standings[2].placement, 3);
+    assert_eq!(standings[2].eliminated_in_round, Some(2));
+    assert_eq!(engine.finish_reason(), None);
+}
+
+#[test]
+fn last_player_wins_when_opponent_is_eliminated() {
+    let (mut engine, players) = start_game(vec![spawn(5, 5, 0), spawn(10, 10, 0)], vec![]);
+
+    engine.eliminate_player(&players[0]);
+    engine.advance_tick();
+
+    assert_eq!(
+        engine.finish_reason(),
+        Some(FinishReason::LAST_PLAYER_STANDING)
+    );
+    assert_eq!(engine.winner().map(|player| player.id), Some(players[1]));
+    assert_eq!(engine.standings()[1].player_id, players[0]);
+}
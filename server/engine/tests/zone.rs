@@ -0,0 +1,144 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::{health, spawn, start_game_with_config};
+use mca_hackathon_engine::{GameConfig, GameEngine, Spawn, Zone, ZoneConfig, ZoneShape};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Creates a started game on the default 30 x 30 field with one player per spawn, the players are
+/// returned in spawn order.
+fn start_game_with_zone(zone_config: ZoneConfig, spawns: Vec<Spawn>) -> (GameEngine, Vec<Uuid>) {
+    let config = GameConfig {
+        zone: Some(zone_config),
+        ..GameConfig::default()
+    };
+
+    return start_game_with_config(config, spawns, vec![]);
+}
+
+fn zone(engine: &GameEngine) -> Zone {
+    return engine.game_state().zone.clone().unwrap();
+}
+
+#[test]
+fn zone_shrinks_on_schedule_until_min_radius() {
+    let zone_config = ZoneConfig {
+        shape: ZoneShape::RECTANGLE,
+        shrink_start_ticks: 2,
+        shrink_interval_ticks: 2,
+        shrink_factor: 0.5,
+        min_radius: 3.0,
+        ..ZoneConfig::default()
+    };
+    let (mut engine, _) =
+        start_game_with_zone(zone_config, vec![spawn(14, 14, 0), spawn(15, 15, 0)]);
+
+    // The zone covers the whole field at the start, the center is between the middle fields
+    let start = zone(&engine);
+    assert_eq!(start.current.center_x, 14.5);
+    assert_eq!(start.current.radius_x, 14.5);
+    assert_eq!(start.next.radius_x, 7.25);
+    assert_eq!(start.ticks_until_shrink, Some(2));
+
+    engine.apply_actions(&HashMap::new());
+    assert_eq!(zone(&engine).current.radius_x, 14.5);
+    assert_eq!(zone(&engine).ticks_until_shrink, Some(1));
+
+    engine.apply_actions(&HashMap::new());
+    assert_eq!(zone(&engine).current.radius_x, 7.25);
+    assert_eq!(zone(&engine).next.radius_x, 3.625);
+    assert_eq!(zone(&engine).ticks_until_shrink, Some(2));
+
+    engine.apply_actions(&HashMap::new());
+    engine.apply_actions(&HashMap::new());
+    assert_eq!(zone(&engine).current.radius_x, 3.625);
+    assert_eq!(zone(&engine).next.radius_x, 3.0);
+
+    // The zone stops shrinking once it reached the minimum radius
+    engine.apply_actions(&HashMap::new());
+    engine.apply_actions(&HashMap::new());
+    assert_eq!(zone(&engine).current.radius_x, 3.0);
+    assert_eq!(zone(&engine).next.radius_x, 3.0);
+    assert_eq!(zone(&engine).ticks_until_shrink, None);
+
+    engine.apply_actions(&HashMap::new());
+    engine.apply_actions(&HashMap::new());
+    assert_eq!(zone(&engine).current.radius_x, 3.0);
+}
+
+#[test]
+fn game_update_announces_next_zone() {
+    let zone_config = ZoneConfig {
+        shape: ZoneShape::RECTANGLE,
+        shrink_start_ticks: 1,
+        shrink_factor: 0.5,
+        ..ZoneConfig::default()
+    };
+    let (mut engine, _) =
+        start_game_with_zone(zone_config, vec![spawn(14, 14, 0), spawn(15, 15, 0)]);
+
+    engine.apply_actions(&HashMap::new());
+
+    let zone_out = engine.game_state_out(0).zone.unwrap();
+    assert_eq!(zone_out.current.radius_y, 7.25);
+    assert_eq!(zone_out.next.radius_y, 3.625);
+    assert_eq!(
+        zone_out.ticks_until_shrink,
+        Some(ZoneConfig::default().shrink_interval_ticks)
+    );
+}
+
+#[test]
+fn players_outside_of_zone_take_damage() {
+    let zone_config = ZoneConfig {
+        shape: ZoneShape::RECTANGLE,
+        shrink_start_ticks: 1,
+        shrink_factor: 0.5,
+        damage_per_tick: 60,
+        ..ZoneConfig::default()
+    };
+    let (mut engine, players) = start_game_with_zone(
+        zone_config,
+        vec![spawn(14, 14, 0), spawn(0, 0, 0), spawn(8, 20, 0)],
+    );
+
+    // The zone shrinks at the end of the first tick and deals damage from the second tick on
+    engine.apply_actions(&HashMap::new());
+    assert_eq!(health(&engine, &players[1]), 100);
+
+    engine.apply_actions(&HashMap::new());
+    assert_eq!(health(&engine, &players[0]), 100);
+    assert_eq!(health(&engine, &players[1]), 40);
+    assert_eq!(health(&engine, &players[2]), 100);
+
+    engine.apply_actions(&HashMap::new());
+    let player = engine.player(&players[1]).unwrap();
+    assert_eq!(player.health, 0);
+    assert_eq!(player.stats.damage_taken, 100);
+}
+
+#[test]
+fn circle_zone_damages_players_outside_of_radius() {
+    let zone_config = ZoneConfig {
+        shape: ZoneShape::CIRCLE,
+        shrink_start_ticks: 1,
+        shrink_factor: 0.5,
+        ..ZoneConfig::default()
+    };
+    // Both players are within the corners of a rectangle of the same radius
+    let (mut engine, players) =
+        start_game_with_zone(zone_config, vec![spawn(14, 7, 0), spawn(7, 7, 0)]);
+
+    assert_eq!(zone(&engine).current.radius_x, 14.5f64.hypot(14.5));
+
+    engine.apply_actions(&HashMap::new());
+    engine.apply_actions(&HashMap::new());
+
+    assert_eq!(health(&engine, &players[0]), 100);
+    assert_eq!(
+        health(&engine, &players[1]),
+        100 - ZoneConfig::default().damage_per_tick
+    );
+}
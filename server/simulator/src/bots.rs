@@ -0,0 +1,71 @@
+use engine::protocol::{ClientHello, GameStateOut};
+use engine::{ClientAction, PlayerAction, WeaponType};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::Bot;
+
+const WEAPONS: [WeaponType; 4] = [
+    WeaponType::RIFLE,
+    WeaponType::SHOTGUN,
+    WeaponType::ROCKET,
+    WeaponType::LASER,
+];
+
+/// Never sends an action.
+pub struct IdleBot;
+
+impl Bot for IdleBot {
+    fn on_game_state(&mut self, _game_state: &GameStateOut) -> Option<PlayerAction> {
+        return None;
+    }
+}
+
+/// Sends a random action every tick. Useful as a baseline opponent.
+pub struct RandomBot {
+    rng: StdRng,
+}
+
+impl RandomBot {
+    pub fn new(seed: u64) -> RandomBot {
+        return RandomBot {
+            rng: StdRng::seed_from_u64(seed),
+        };
+    }
+}
+
+impl Bot for RandomBot {
+    fn on_hello(&mut self, hello: &ClientHello) {
+        // Mix the player id into the seed, so several random bots in one game behave differently
+        self.rng = StdRng::seed_from_u64(self.rng.gen::<u64>() ^ hello.player_id.as_u64_pair().0);
+    }
+
+    fn on_game_state(&mut self, _game_state: &GameStateOut) -> Option<PlayerAction> {
+        let action = match self.rng.gen_range(0..8) {
+            0 => ClientAction::SHOOT,
+            1 => ClientAction::RELOAD,
+            2 => ClientAction::TURN,
+            3 => ClientAction::UP,
+            4 => ClientAction::DOWN,
+            5 => ClientAction::LEFT,
+            6 => ClientAction::SWITCH_WEAPON,
+            _ => ClientAction::RIGHT,
+        };
+
+        let degrees = match action {
+            ClientAction::TURN => Some(self.rng.gen_range(0..8) * 45),
+            _ => None,
+        };
+
+        let weapon = match action {
+            ClientAction::SWITCH_WEAPON => Some(WEAPONS[self.rng.gen_range(0..WEAPONS.len())]),
+            _ => None,
+        };
+
+        return Some(PlayerAction {
+            action,
+            degrees,
+            weapon,
+        });
+    }
+}
@@ -0,0 +1,33 @@
+#![allow(clippy::needless_return, clippy::upper_case_acronyms)]
+
+//! Runs games entirely in-process, without websockets or tick timers, so bots can be trained on
+//! thousands of matches.
+//!
+//! Bots are either Rust implementations of the [`Bot`] trait or child processes speaking the JSON
+//! protocol of the game server over stdin and stdout (see [`ProcessBot`]).
+
+use engine::protocol::{ClientHello, GameStateOut};
+use engine::PlayerAction;
+
+mod bots;
+mod process_bot;
+mod runner;
+
+pub use bots::{IdleBot, RandomBot};
+pub use process_bot::ProcessBot;
+pub use runner::{
+    run_game, run_simulation, Contestant, ContestantStats, GameResult, SimulationOptions,
+    SimulationSummary,
+};
+
+pub trait Bot {
+    /// Called once after the bot joined a game, with the same message the server sends after a
+    /// websocket connection was established.
+    fn on_hello(&mut self, _hello: &ClientHello) {}
+
+    /// Called for every tick of the game. Returning `None` leaves the player idle for the tick.
+    fn on_game_state(&mut self, game_state: &GameStateOut) -> Option<PlayerAction>;
+
+    /// Called once with the final game state after the game is over.
+    fn on_game_over(&mut self, _game_state: &GameStateOut) {}
+}
@@ -0,0 +1,184 @@
+#![allow(clippy::needless_return, clippy::upper_case_acronyms)]
+
+use std::env;
+use std::fs;
+use std::process;
+use std::time::Duration;
+
+use engine::{GameConfig, GameMap, GENERATED_MAP_NAME};
+use mca_hackathon_simulator::{
+    run_simulation, Bot, Contestant, IdleBot, RandomBot, SimulationOptions, SimulationSummary,
+};
+
+const DEFAULT_RESPONSE_TIMEOUT_MILLI_SECONDS: u64 = 1000;
+
+const USAGE: &str = "Usage: simulator [OPTIONS] <NAME>=<BOT> <NAME>=<BOT> ...
+
+Plays games between the given bots without any networking and prints win/loss statistics.
+
+<BOT> is either 'builtin:idle', 'builtin:random' or a command, which is started for every game
+and exchanges JSON messages with the simulator over stdin and stdout.
+
+Options:
+  --games <N>        Number of games to play (default: 100)
+  --seed <N>         Seed of the first game, following games use the next seeds (default: 0)
+  --threads <N>      Number of games played in parallel (default: number of CPUs)
+  --config <FILE>    JSON file with the game configuration, same format as when creating a lobby
+  --map <FILE>       JSON map file, which replaces the layout of the game configuration, or
+                     'generated' to play every game on a new generated map
+  --timeout <MS>     Time a bot process may take to answer a game update (default: 1000)
+  --json             Print the statistics as JSON
+  --help             Print this help";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match run(args) {
+        Ok(()) => {}
+        Err(error_message) => {
+            eprintln!("{}\n\n{}", error_message, USAGE);
+            process::exit(1);
+        }
+    }
+}
+
+fn run(args: Vec<String>) -> Result<(), String> {
+    let mut options = SimulationOptions::default();
+    let mut response_timeout = Duration::from_millis(DEFAULT_RESPONSE_TIMEOUT_MILLI_SECONDS);
+    let mut print_json = false;
+    let mut bot_specs = vec![];
+    let mut map = None;
+
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--games" => options.games = parse_number(&arg, args.next())?,
+            "--seed" => options.seed = parse_number(&arg, args.next())?,
+            "--threads" => options.threads = parse_number(&arg, args.next())?,
+            "--timeout" => {
+                response_timeout = Duration::from_millis(parse_number(&arg, args.next())?)
+            }
+            "--config" => options.config = read_config(args.next())?,
+            "--map" => {
+                map = Some(
+                    args.next()
+                        .ok_or_else(|| "'--map' needs a value".to_string())?,
+                )
+            }
+            "--json" => print_json = true,
+            "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ => bot_specs.push(arg),
+        }
+    }
+
+    match map.as_deref() {
+        Some(GENERATED_MAP_NAME) => options.config.map = Some(GENERATED_MAP_NAME.to_string()),
+        Some(path) => read_map(path)?.apply_to(&mut options.config),
+        None => {}
+    }
+
+    if bot_specs.is_empty() {
+        return Err("No bots were given".to_string());
+    }
+
+    let contestants = bot_specs
+        .iter()
+        .map(|bot_spec| parse_contestant(bot_spec, response_timeout))
+        .collect::<Result<Vec<Contestant>, String>>()?;
+
+    let summary = run_simulation(&contestants, &options)?;
+
+    if print_json {
+        println!("{}", serde_json::to_string_pretty(&summary).unwrap());
+    } else {
+        print_summary(&summary);
+    }
+
+    return Ok(());
+}
+
+fn parse_number<T: std::str::FromStr>(option: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("'{}' needs a value", option))?;
+
+    return value
+        .parse()
+        .map_err(|_| format!("'{}' is not a valid value for '{}'", value, option));
+}
+
+fn read_config(path: Option<String>) -> Result<GameConfig, String> {
+    let path = path.ok_or_else(|| "'--config' needs a value".to_string())?;
+
+    let content = fs::read_to_string(&path)
+        .map_err(|err| format!("Could not read config '{}'. Original error: {}.", path, err))?;
+
+    return engine::deserialize_config(&mut serde_json::Deserializer::from_str(&content)).map_err(
+        |err| {
+            format!(
+                "Could not parse config '{}'. Original error: {}.",
+                path, err
+            )
+        },
+    );
+}
+
+fn read_map(path: &str) -> Result<GameMap, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|err| format!("Could not read map '{}'. Original error: {}.", path, err))?;
+
+    let map: GameMap = serde_json::from_str(&content)
+        .map_err(|err| format!("Could not parse map '{}'. Original error: {}.", path, err))?;
+
+    map.validate()?;
+
+    return Ok(map);
+}
+
+fn parse_contestant(bot_spec: &str, response_timeout: Duration) -> Result<Contestant, String> {
+    let (name, bot) = match bot_spec.split_once('=') {
+        Some((name, bot)) if !name.is_empty() && !bot.is_empty() => (name, bot),
+        _ => {
+            return Err(format!(
+                "'{}' is not a valid bot, expected <NAME>=<BOT>",
+                bot_spec
+            ))
+        }
+    };
+
+    return match bot {
+        "builtin:idle" => Ok(Contestant::new(name, |_| {
+            Ok(Box::new(IdleBot) as Box<dyn Bot>)
+        })),
+        "builtin:random" => Ok(Contestant::new(name, |seed| {
+            Ok(Box::new(RandomBot::new(seed)) as Box<dyn Bot>)
+        })),
+        _ if bot.starts_with("builtin:") => Err(format!("'{}' is not a builtin bot", bot)),
+        _ => Ok(Contestant::process(name, bot, response_timeout)),
+    };
+}
+
+fn print_summary(summary: &SimulationSummary) {
+    println!(
+        "Played {} games, {:.1} rounds on average",
+        summary.games, summary.average_rounds
+    );
+    println!();
+    println!(
+        "{:<20} {:>8} {:>8} {:>8} {:>9}",
+        "Bot", "Wins", "Losses", "Draws", "Win rate"
+    );
+
+    for stats in &summary.contestants {
+        println!(
+            "{:<20} {:>8} {:>8} {:>8} {:>8.1}%",
+            stats.name,
+            stats.wins,
+            stats.losses,
+            stats.draws,
+            stats.win_rate * 100.0
+        );
+    }
+}
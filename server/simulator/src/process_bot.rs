@@ -0,0 +1,142 @@
+use engine::protocol::{ClientHello, ClientMessage, GameStateOut};
+use engine::PlayerAction;
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::Bot;
+
+/// A bot running as a child process. Instead of a websocket, the process receives the messages of
+/// the server as JSON lines on stdin and answers with JSON lines on stdout.
+///
+/// Answers which reference another tick or arrive after the response timeout are ignored, in the
+/// same way the game server ignores late messages.
+pub struct ProcessBot {
+    command: String,
+    child: Child,
+    stdin: Option<ChildStdin>,
+    lines: Receiver<String>,
+    response_timeout: Duration,
+}
+
+impl ProcessBot {
+    /// Starts the command with `sh -c`, so arguments and environment variables can be passed
+    /// along with the command.
+    pub fn spawn(command: &str, response_timeout: Duration) -> Result<ProcessBot, String> {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|err| {
+                format!(
+                    "Could not start bot '{}'. Original error: {}.",
+                    command, err
+                )
+            })?;
+
+        let stdin = child.stdin.take();
+        let stdout = child.stdout.take().unwrap();
+
+        let (sender, lines) = mpsc::channel();
+
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                match line {
+                    Ok(line) => {
+                        if sender.send(line).is_err() {
+                            return;
+                        }
+                    }
+                    Err(_) => return,
+                }
+            }
+        });
+
+        return Ok(ProcessBot {
+            command: command.to_string(),
+            child,
+            stdin,
+            lines,
+            response_timeout,
+        });
+    }
+
+    fn send<T: Serialize>(&mut self, message: &T) {
+        let stdin = match &mut self.stdin {
+            Some(stdin) => stdin,
+            None => return,
+        };
+
+        let mut line = serde_json::to_string(message).unwrap();
+        line.push('\n');
+
+        if stdin
+            .write_all(line.as_bytes())
+            .and_then(|_| stdin.flush())
+            .is_err()
+        {
+            eprintln!(
+                "Bot '{}' closed its input, it will no longer receive messages",
+                self.command
+            );
+            self.stdin = None;
+        }
+    }
+}
+
+impl Bot for ProcessBot {
+    fn on_hello(&mut self, hello: &ClientHello) {
+        self.send(hello);
+    }
+
+    fn on_game_state(&mut self, game_state: &GameStateOut) -> Option<PlayerAction> {
+        self.send(game_state);
+        self.stdin.as_ref()?;
+
+        let deadline = Instant::now() + self.response_timeout;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+
+            let line = match self.lines.recv_timeout(remaining) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => return None,
+                Err(RecvTimeoutError::Disconnected) => {
+                    self.stdin = None;
+                    return None;
+                }
+            };
+
+            match serde_json::from_str::<ClientMessage>(&line) {
+                Ok(client_message) if client_message.tick == game_state.tick => {
+                    return Some(client_message.player_action);
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    eprintln!(
+                        "Ignoring message of bot '{}', because it could not be parsed. Original error: {}.",
+                        self.command, err
+                    );
+                }
+            }
+        }
+    }
+
+    fn on_game_over(&mut self, game_state: &GameStateOut) {
+        self.send(game_state);
+    }
+}
+
+impl Drop for ProcessBot {
+    fn drop(&mut self) {
+        self.stdin = None;
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
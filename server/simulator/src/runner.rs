@@ -0,0 +1,278 @@
+use engine::{FinishReason, GameConfig, GameEngine, GameMap, GENERATED_MAP_NAME};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::process_bot::ProcessBot;
+use crate::Bot;
+
+type BotFactory = Box<dyn Fn(u64) -> Result<Box<dyn Bot>, String> + Send + Sync>;
+
+/// Result of a game together with the contestant indexes in the order they joined the game.
+type SimulatedGame = (Vec<usize>, GameResult);
+
+/// A named participant of a simulation. A new bot is created for every game, the factory receives
+/// the seed of the game.
+pub struct Contestant {
+    pub name: String,
+    factory: BotFactory,
+}
+
+impl Contestant {
+    pub fn new<F>(name: &str, factory: F) -> Contestant
+    where
+        F: Fn(u64) -> Result<Box<dyn Bot>, String> + Send + Sync + 'static,
+    {
+        return Contestant {
+            name: name.to_string(),
+            factory: Box::new(factory),
+        };
+    }
+
+    /// A contestant which starts the command as a new child process for every game.
+    pub fn process(name: &str, command: &str, response_timeout: Duration) -> Contestant {
+        let command = command.to_string();
+
+        return Contestant::new(name, move |_| {
+            let bot = ProcessBot::spawn(&command, response_timeout)?;
+            return Ok(Box::new(bot) as Box<dyn Bot>);
+        });
+    }
+}
+
+pub struct SimulationOptions {
+    pub games: usize,
+    pub config: GameConfig,
+    /// Seed of the first game, every following game uses the next seed.
+    pub seed: u64,
+    pub threads: usize,
+}
+
+impl Default for SimulationOptions {
+    fn default() -> Self {
+        SimulationOptions {
+            games: 100,
+            config: GameConfig::default(),
+            seed: 0,
+            threads: thread::available_parallelism()
+                .map(|threads| threads.get())
+                .unwrap_or(1),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GameResult {
+    pub seed: u64,
+    pub rounds: i32,
+    pub finish_reason: Option<FinishReason>,
+    /// Index of the winning bot. `None` if the game ended in a draw.
+    pub winner: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ContestantStats {
+    pub name: String,
+    pub wins: usize,
+    pub losses: usize,
+    pub draws: usize,
+    pub win_rate: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SimulationSummary {
+    pub games: usize,
+    pub average_rounds: f64,
+    pub contestants: Vec<ContestantStats>,
+}
+
+/// Plays a single game until the engine reports that it is over. The named bots join the game in
+/// the given order, which determines their starting positions.
+///
+/// The last player alive wins the game. If the maximum of rounds is reached, the player with the
+/// most health wins, unless several players share the highest health.
+pub fn run_game(
+    config: GameConfig,
+    players: Vec<(String, Box<dyn Bot>)>,
+) -> Result<GameResult, String> {
+    let mut engine = GameEngine::new(config);
+    let mut player_ids: Vec<Uuid> = vec![];
+    let mut bots: Vec<Box<dyn Bot>> = vec![];
+
+    for (name, bot) in players {
+        player_ids.push(engine.add_player(name)?);
+        bots.push(bot);
+    }
+
+    engine.start();
+
+    for (bot, player_id) in bots.iter_mut().zip(&player_ids) {
+        bot.on_hello(&engine::protocol::ClientHello {
+            success: true,
+            player_id: *player_id,
+            message: "Connection successful.".to_string(),
+            session_token: None,
+        });
+    }
+
+    loop {
+        let game_state_out = engine.game_state_out(0);
+
+        let player_actions: HashMap<Uuid, engine::PlayerAction> = bots
+            .iter_mut()
+            .zip(&player_ids)
+            .filter_map(|(bot, player_id)| {
+                bot.on_game_state(&game_state_out)
+                    .map(|player_action| (*player_id, player_action))
+            })
+            .collect();
+
+        let outcome = engine.apply_actions(&player_actions);
+
+        if outcome.finish_reason.is_some() {
+            break;
+        }
+    }
+
+    let game_state_out = engine.game_state_out(0);
+
+    for bot in bots.iter_mut() {
+        bot.on_game_over(&game_state_out);
+    }
+
+    let winner = engine.winner().and_then(|player| {
+        player_ids
+            .iter()
+            .position(|player_id| *player_id == player.id)
+    });
+
+    return Ok(GameResult {
+        seed: engine.seed(),
+        rounds: engine.round(),
+        finish_reason: engine.finish_reason(),
+        winner,
+    });
+}
+
+/// Plays the configured number of games on several threads and aggregates the results.
+///
+/// The order in which the contestants join rotates from game to game, so no contestant always
+/// gets the same starting position.
+pub fn run_simulation(
+    contestants: &[Contestant],
+    options: &SimulationOptions,
+) -> Result<SimulationSummary, String> {
+    if contestants.is_empty() {
+        return Err("At least one contestant is needed to run a simulation".to_string());
+    }
+
+    options.config.validate()?;
+
+    let next_game = AtomicUsize::new(0);
+    let results: Mutex<Vec<Result<SimulatedGame, String>>> = Mutex::new(vec![]);
+
+    thread::scope(|scope| {
+        for _ in 0..std::cmp::max(1, options.threads) {
+            scope.spawn(|| loop {
+                let game = next_game.fetch_add(1, Ordering::SeqCst);
+
+                if game >= options.games {
+                    return;
+                }
+
+                let result = run_simulated_game(contestants, options, game);
+                let failed = result.is_err();
+
+                results.lock().unwrap().push(result);
+
+                if failed {
+                    next_game.store(options.games, Ordering::SeqCst);
+                    return;
+                }
+            });
+        }
+    });
+
+    let mut contestant_stats: Vec<ContestantStats> = contestants
+        .iter()
+        .map(|contestant| ContestantStats {
+            name: contestant.name.clone(),
+            wins: 0,
+            losses: 0,
+            draws: 0,
+            win_rate: 0.0,
+        })
+        .collect();
+
+    let mut games = 0;
+    let mut total_rounds = 0;
+
+    for result in results.into_inner().unwrap() {
+        let (join_order, game_result) = result?;
+
+        games += 1;
+        total_rounds += game_result.rounds as i64;
+
+        let winner = game_result.winner.map(|bot_index| join_order[bot_index]);
+
+        for (index, stats) in contestant_stats.iter_mut().enumerate() {
+            match winner {
+                Some(winner) if winner == index => stats.wins += 1,
+                Some(_) => stats.losses += 1,
+                None => stats.draws += 1,
+            }
+        }
+    }
+
+    for stats in contestant_stats.iter_mut() {
+        if games > 0 {
+            stats.win_rate = stats.wins as f64 / games as f64;
+        }
+    }
+
+    return Ok(SimulationSummary {
+        games,
+        average_rounds: if games > 0 {
+            total_rounds as f64 / games as f64
+        } else {
+            0.0
+        },
+        contestants: contestant_stats,
+    });
+}
+
+fn run_simulated_game(
+    contestants: &[Contestant],
+    options: &SimulationOptions,
+    game: usize,
+) -> Result<SimulatedGame, String> {
+    let seed = options.seed.wrapping_add(game as u64);
+
+    let join_order: Vec<usize> = (0..contestants.len())
+        .map(|index| (index + game) % contestants.len())
+        .collect();
+
+    let mut players = vec![];
+
+    for index in &join_order {
+        let contestant = &contestants[*index];
+        players.push((contestant.name.clone(), (contestant.factory)(seed)?));
+    }
+
+    let mut config = options.config.clone();
+    config.seed = Some(seed);
+
+    if config.map.as_deref() == Some(GENERATED_MAP_NAME) {
+        // Every game is played on a new map, unless the configuration fixes the map seed
+        config.map_seed.get_or_insert(seed);
+        GameMap::apply_generated_to(&mut config)?;
+    }
+
+    let game_result = run_game(config, players)?;
+
+    return Ok((join_order, game_result));
+}
@@ -2,6 +2,9 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::models;
+use crate::tournament;
+
+pub use engine::protocol::{ClientHello, ClientMessage, GameStateOut};
 
 #[derive(Serialize)]
 pub struct ServerOut {
@@ -14,6 +17,48 @@ pub struct LobbyOut {
     pub clients: Vec<models::Client>,
     pub status: models::LobbyStatus,
     pub spectators: i32,
+    pub config: engine::GameConfig,
+    pub replay: Option<ReplayPlaybackOut>,
+    pub tournament_id: Option<Uuid>,
+}
+
+#[derive(Serialize)]
+pub struct ReplayPlaybackOut {
+    pub file_name: String,
+    pub round: i32,
+    pub rounds: i32,
+    pub paused: bool,
+    pub speed: f64,
+}
+
+#[derive(Serialize)]
+pub struct ReplaysOut {
+    pub replays: Vec<ReplayOut>,
+}
+
+#[derive(Serialize)]
+pub struct ReplayOut {
+    pub file_name: String,
+}
+
+#[derive(Serialize)]
+pub struct ReplayVerificationOut {
+    pub file_name: String,
+    pub deterministic: bool,
+    pub verified_rounds: i32,
+    pub mismatch_round: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateReplayLobbyBody {
+    pub file_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateReplayLobbyBody {
+    pub paused: Option<bool>,
+    pub round: Option<i32>,
+    pub speed: Option<f64>,
 }
 
 #[derive(Serialize)]
@@ -21,40 +66,62 @@ pub struct LobbyCreateResponse {
     pub id: Uuid,
 }
 
+#[derive(Debug, Deserialize, Default)]
+pub struct CreateLobbyBody {
+    #[serde(flatten, deserialize_with = "engine::deserialize_config")]
+    pub config: engine::GameConfig,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct UpdateLobbyBody {
     pub status: models::LobbyStatus,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct CreateTournamentBody {
+    pub name: Option<String>,
+    pub format: tournament::TournamentFormat,
+    pub participants: Vec<String>,
+    #[serde(default, deserialize_with = "engine::deserialize_config")]
+    pub config: engine::GameConfig,
+    pub connect_timeout_seconds: Option<u64>,
+}
+
 #[derive(Serialize)]
-pub struct GameStateOut {
-    pub tick: Uuid,
-    pub tick_length_milli_seconds: u64,
-    pub players: Vec<models::Player>,
-    pub entities: Vec<models::Projectile>,
-    pub spectators: i32,
+pub struct TournamentsOut {
+    pub tournaments: Vec<tournament::Tournament>,
 }
 
 #[derive(Serialize)]
-pub struct ClientHello {
-    pub success: bool,
-    pub message: String,
-    pub player_id: Uuid,
+pub struct TournamentCreateResponse {
+    pub id: Uuid,
 }
 
-#[derive(Deserialize, Clone)]
-pub enum ClientAction {
-    SHOOT,
-    TURN,
-    UP,
-    DOWN,
-    LEFT,
-    RIGHT,
+#[derive(Serialize)]
+pub struct LeaderboardEntry {
+    pub rank: usize,
+    pub name: String,
+    pub rating: f64,
+    pub games: i32,
+    pub wins: i32,
+}
+
+#[derive(Serialize)]
+pub struct LeaderboardOut {
+    pub players: Vec<LeaderboardEntry>,
 }
 
-#[derive(Deserialize, Clone)]
-pub struct ClientMessage {
-    pub tick: Uuid,
-    pub action: ClientAction,
-    pub degrees: Option<i32>,
+#[derive(Serialize)]
+pub struct MapOut {
+    pub name: String,
+    pub field_size_x: i32,
+    pub field_size_y: i32,
+    pub max_players: Option<usize>,
+    pub obstacles: usize,
+    pub pickups: usize,
+}
+
+#[derive(Serialize)]
+pub struct MapsOut {
+    pub maps: Vec<MapOut>,
 }
@@ -0,0 +1,82 @@
+use log::{info, warn};
+use std::env;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Access {
+    READ,
+    WRITE,
+}
+
+#[derive(Debug)]
+pub struct Unauthorized;
+
+impl warp::reject::Reject for Unauthorized {}
+
+/// API keys which grant access to the management API. Keys are read from the comma separated
+/// `MANAGEMENT_API_KEYS` environment variable and have to be supplied as bearer token.
+pub struct ManagementApiAuth {
+    api_keys: Vec<String>,
+    public_reads: bool,
+}
+
+impl ManagementApiAuth {
+    pub fn from_env() -> ManagementApiAuth {
+        let api_keys: Vec<String> = env::var("MANAGEMENT_API_KEYS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|api_key| api_key.trim().to_string())
+            .filter(|api_key| !api_key.is_empty())
+            .collect();
+
+        let public_reads = !matches!(
+            env::var("MANAGEMENT_API_PUBLIC_READS").as_deref(),
+            Ok("false") | Ok("0")
+        );
+
+        if api_keys.is_empty() {
+            warn!("No management API keys configured, everyone is allowed to manage lobbies. Set MANAGEMENT_API_KEYS to restrict access.");
+        } else {
+            info!(
+                "Management API is protected by {} API key(s), read-only endpoints are {}",
+                api_keys.len(),
+                if public_reads { "public" } else { "protected" }
+            );
+        }
+
+        return ManagementApiAuth {
+            api_keys,
+            public_reads,
+        };
+    }
+
+    /// Checks the value of an `Authorization` header. Access is granted to everyone if no API keys
+    /// are configured.
+    pub fn is_authorized(&self, authorization: Option<&str>, access: Access) -> bool {
+        if self.api_keys.is_empty() || (access == Access::READ && self.public_reads) {
+            return true;
+        }
+
+        let token = match authorization.and_then(|value| value.strip_prefix("Bearer ")) {
+            Some(token) => token.trim(),
+            None => return false,
+        };
+
+        return self
+            .api_keys
+            .iter()
+            .any(|api_key| constant_time_equals(api_key.as_bytes(), token.as_bytes()));
+    }
+}
+
+// Compares all bytes, so the time taken does not tell how much of a guessed secret was correct
+pub fn constant_time_equals(left: &[u8], right: &[u8]) -> bool {
+    if left.len() != right.len() {
+        return false;
+    }
+
+    return left
+        .iter()
+        .zip(right.iter())
+        .fold(0, |difference, (l, r)| difference | (l ^ r))
+        == 0;
+}
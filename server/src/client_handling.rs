@@ -1,6 +1,8 @@
+use engine::protocol::{
+    ActionRejected, ActionRejectedReason, ServerMessage, LEGACY_PROTOCOL_VERSION,
+};
 use futures_util::stream::SplitStream;
 use futures_util::{future, pin_mut, SinkExt, TryStreamExt};
-use serde_json::Error;
 use std::net::SocketAddr;
 use tokio::net::TcpStream;
 use tokio_tungstenite::tungstenite::Message;
@@ -46,7 +48,7 @@ pub async fn listen_for_messages(
     let mut server = server_arc.lock().await;
     let lobby = server.lobbies.get_mut(&lobby_id).unwrap();
 
-    game::handle_client_disconnect(lobby, addr, db_arc.clone());
+    game::handle_client_disconnect(lobby, addr, server_arc.clone(), db_arc.clone());
 
     let mut db = db_arc.lock().await;
     db.connections.remove(&addr);
@@ -63,17 +65,9 @@ async fn process_message_of_client(
 
     let lobby = server.lobbies.get_mut(&lobby_id).unwrap();
 
-    if !matches!(lobby.status, models::LobbyStatus::RUNNING) {
+    if message.is_close() || message.is_ping() || message.is_pong() {
         info!(
-            "Skipping message of client with address '{}'. Lobby is not running at the moment.",
-            addr
-        );
-        return;
-    }
-
-    if message.is_close() {
-        info!(
-            "Skipping message of client with address '{}'. The client disconnected.",
+            "Skipping control message of client with address '{}'.",
             addr
         );
         return;
@@ -87,39 +81,67 @@ async fn process_message_of_client(
 
     let client = client_option.unwrap();
 
+    if !matches!(lobby.status, models::LobbyStatus::RUNNING) {
+        reject_message(
+            lobby,
+            addr,
+            ActionRejectedReason::LOBBY_NOT_RUNNING,
+            "Lobby is not running at the moment".to_string(),
+            db_arc.clone(),
+        );
+        return;
+    }
+
     if !matches!(client.client_type, models::ClientType::PLAYER,) {
-        info!(
-            "Skipping message of non PLAYER client with address '{}'.",
-            addr
+        reject_message(
+            lobby,
+            addr,
+            ActionRejectedReason::NOT_A_PLAYER,
+            "Only players can submit actions".to_string(),
+            db_arc.clone(),
         );
         return;
     }
 
-    let result: Result<ClientMessage, Error> = serde_json::from_str(&message.to_string());
+    let result: Result<ClientMessage, String> =
+        engine::protocol::parse_player_message(&message.to_string(), client.protocol_version);
     match result {
         Err(err) => {
-            info!(
-                "Failed to parse message from client with address '{}'. Original error: {}.",
-                addr, err
+            reject_message(
+                lobby,
+                addr,
+                ActionRejectedReason::INVALID_MESSAGE,
+                format!("Message could not be parsed: {}", err),
+                db_arc.clone(),
             );
             return;
         }
         Ok(client_message) => {
-            if lobby.client_messages.get(&addr).is_some() {
-                info!(
-                            "Skipping message, because client with adddress '{}' supplied duplicate message during game tick.",
-                            addr
-                        );
-                return;
-            }
-
             let client_tick = client_message.tick;
-            let game_tick = lobby.tick;
+            let game_tick = lobby.engine.tick();
 
             if client_tick != game_tick {
-                info!(
-                    "Skipping message, because client with adddress '{}' used invalid tick '{}'. Current tick: '{}'.",
-                    addr, game_tick, client_tick
+                reject_message(
+                    lobby,
+                    addr,
+                    ActionRejectedReason::INVALID_TICK,
+                    format!(
+                        "Tick '{}' is not the current tick '{}'",
+                        client_tick, game_tick
+                    ),
+                    db_arc.clone(),
+                );
+                lobby.late_client_messages.push((addr, client_message));
+                return;
+            }
+
+            if lobby.client_messages.contains_key(&addr) {
+                reject_message(
+                    lobby,
+                    addr,
+                    ActionRejectedReason::DUPLICATE_ACTION,
+                    "An action was already submitted for this tick".to_string(),
+                    db_arc.clone(),
                 );
                 return;
             }
@@ -131,6 +153,53 @@ async fn process_message_of_client(
     }
 }
 
+/// Informs the client why its message was not processed. Clients of the legacy protocol answer
+/// every message with an action, so the rejection is only logged for them.
+fn reject_message(
+    lobby: &models::Lobby,
+    addr: SocketAddr,
+    reason: ActionRejectedReason,
+    message: String,
+    db_arc: models::DbArc,
+) {
+    info!(
+        "Rejecting message of client with address '{}' ({:?}): {}",
+        addr, reason, message
+    );
+
+    let protocol_version = match lobby.clients.get(&addr) {
+        Some(client) => client.protocol_version,
+        None => return,
+    };
+
+    if protocol_version == LEGACY_PROTOCOL_VERSION {
+        return;
+    }
+
+    let action_rejected = ServerMessage::ACTION_REJECTED(ActionRejected {
+        reason,
+        message,
+        tick: lobby.engine.tick(),
+    });
+
+    tokio::spawn(send_message_to_addr(
+        addr,
+        Message::Text(action_rejected.to_json(protocol_version)),
+        db_arc,
+    ));
+}
+
+/// Sends the messages one after another, so the client receives them in order.
+pub async fn send_messages_to_addr(
+    addr: SocketAddr,
+    messages: Vec<Message>,
+    db_arc: models::DbArc,
+) {
+    for message in messages {
+        send_message_to_addr(addr, message, db_arc.clone()).await;
+    }
+}
+
 pub async fn send_message_to_addr(addr: SocketAddr, message: Message, db_arc: models::DbArc) {
     let mut db = db_arc.lock().await;
 
@@ -138,10 +207,7 @@ pub async fn send_message_to_addr(addr: SocketAddr, message: Message, db_arc: mo
 
     db.connections
         .get_mut(&addr)
-        .expect(&format!(
-            "No connection found for client with address '{}'",
-            addr
-        ))
+        .unwrap_or_else(|| panic!("No connection found for client with address '{}'", addr))
         .write_stream
         .send(message)
         .await
@@ -1,11 +1,11 @@
-use lazy_static::lazy_static;
+use engine::protocol::{GameOver, GameOverReason, ServerMessage};
+use engine::PlayerAction;
 use log::info;
 use std::collections::HashMap;
 use std::collections::HashSet;
-use std::f64::consts::PI;
+use std::env;
 use std::net::SocketAddr;
-use std::time::Duration;
-use std::vec;
+use std::time::{Duration, Instant};
 use tokio::task::JoinHandle;
 use tokio::time;
 use uuid::Uuid;
@@ -13,72 +13,10 @@ use uuid::Uuid;
 use crate::api_models;
 use crate::client_handling;
 use crate::models;
-use crate::models::Player;
-
-const MAX_FIELD_SIZE_X: i32 = 30;
-const MAX_FIELD_SIZE_Y: i32 = 30;
-const MAX_ROUNDS: i32 = 5000;
-const PROJECTILE_UNIT_LENGTH_TRAVEL_DISTANCE: f64 = 6.0;
-pub const GAME_TICK_LENGTH: u64 = 500;
-
-lazy_static! {
-    static ref PLAYER_COUNT_TO_POSITIONS: HashMap<usize, Vec<(i32, i32, i32)>> = {
-        let mut m = HashMap::new();
-        m.insert(0, vec![]);
-        m.insert(1, vec![(14, 14, 0)]);
-        m.insert(2, vec![(5, 14, 270), (24, 14, 90)]);
-        m.insert(3, vec![(5, 5, 270), (24, 5, 90), (14, 24, 0)]);
-        m.insert(
-            4,
-            vec![(5, 5, 270), (24, 5, 90), (5, 24, 270), (24, 24, 90)],
-        );
-        m.insert(
-            5,
-            vec![
-                (5, 5, 270),
-                (24, 5, 90),
-                (5, 24, 270),
-                (24, 24, 90),
-                (14, 14, 0),
-            ],
-        );
-        m.insert(
-            6,
-            vec![
-                (5, 5, 270),
-                (5, 14, 270),
-                (5, 24, 270),
-                (24, 5, 90),
-                (24, 14, 90),
-                (24, 24, 90),
-            ],
-        );
-        m.insert(
-            7,
-            vec![
-                (5, 5, 270),
-                (5, 14, 270),
-                (5, 24, 270),
-                (24, 5, 90),
-                (24, 14, 90),
-                (24, 24, 90),
-                (14, 14, 0),
-            ],
-        );
-        m
-    };
-    static ref PLAYER_COUNT_TO_COLOR: HashMap<usize, String> = {
-        let mut m = HashMap::new();
-        m.insert(1, "#FF0000".to_string());
-        m.insert(2, "#00FF00".to_string());
-        m.insert(3, "#0000FF".to_string());
-        m.insert(4, "#C800FF".to_string());
-        m.insert(5, "#00FFE1".to_string());
-        m.insert(6, "#FF9D00".to_string());
-        m.insert(7, "#0F754C".to_string());
-        m
-    };
-}
+use crate::ratings;
+use crate::replay;
+
+const DEFAULT_RECONNECT_GRACE_PERIOD_MILLI_SECONDS: u64 = 30000;
 
 pub async fn start_game_for_lobby(
     lobby_id: Uuid,
@@ -90,12 +28,11 @@ pub async fn start_game_for_lobby(
     let lobby = server.lobbies.get_mut(&lobby_id).unwrap();
 
     lobby.client_messages = HashMap::new();
-    lobby.round = 0;
+    lobby.result = None;
 
-    lobby.game_state.entities = vec![];
+    lobby.engine.start();
 
-    let _ = update_initial_player_positions(lobby);
-    reset_player_health(lobby);
+    replay::start_recording(lobby);
 
     tokio::spawn(run_game_for_lobby(
         lobby_id,
@@ -104,37 +41,6 @@ pub async fn start_game_for_lobby(
     ));
 }
 
-fn reset_player_health(lobby: &mut models::Lobby) {
-    lobby.game_state.players.values_mut().for_each(|player| {
-        player.health = 100;
-    });
-}
-
-fn update_initial_player_positions(lobby: &mut models::Lobby) -> Result<(), String> {
-    let player_count = lobby.game_state.players.len();
-    if player_count > PLAYER_COUNT_TO_POSITIONS.len() {
-        return Err(
-            "Cannot add player, because no starting formation is maintained for the player count."
-                .to_string(),
-        );
-    }
-
-    let starting_positions = PLAYER_COUNT_TO_POSITIONS.get(&player_count).unwrap();
-
-    lobby
-        .game_state
-        .players
-        .values_mut()
-        .enumerate()
-        .for_each(|(index, player)| {
-            player.x = starting_positions[index].0;
-            player.y = starting_positions[index].1;
-            player.rotation = starting_positions[index].2;
-        });
-
-    return Ok(());
-}
-
 async fn run_game_for_lobby(lobby_id: Uuid, server_arc: models::ServerArc, db_arc: models::DbArc) {
     info!("Starting game for lobby with id '{}'.", lobby_id);
 
@@ -143,7 +49,7 @@ async fn run_game_for_lobby(lobby_id: Uuid, server_arc: models::ServerArc, db_ar
     let lobby = server.lobbies.get_mut(&lobby_id).unwrap();
 
     ping_clients_in_lobby(
-        lobby.tick,
+        lobby.engine.tick(),
         lobby_id,
         server_arc.clone(),
         db_arc.clone(),
@@ -161,7 +67,7 @@ async fn ping_clients_in_lobby(
 ) {
     info!("Running update of game state");
 
-    if expected_tick != lobby.tick {
+    if expected_tick != lobby.engine.tick() {
         info!(
             "Skipping scheduled update of clients for tick '{}'",
             expected_tick
@@ -172,75 +78,150 @@ async fn ping_clients_in_lobby(
 
     if lobby.status != models::LobbyStatus::RUNNING {
         info!("Skipping game update, because lobby is no longer running");
+        replay::stop_recording(lobby);
         return;
     }
 
-    let game_state = &mut lobby.game_state;
+    let tick_input = replay::collect_tick_input(lobby);
 
-    lobby
+    let player_actions: HashMap<Uuid, PlayerAction> = lobby
         .client_messages
         .iter()
-        .for_each(|(addr, client_message)| {
-            handle_client_message(client_message.clone(), addr, game_state);
-        });
+        .filter_map(|(addr, client_message)| {
+            lobby
+                .players
+                .get(addr)
+                .map(|player_id| (*player_id, client_message.player_action.clone()))
+        })
+        .collect();
 
-    game_state
-        .players
-        .values_mut()
-        .for_each(|player| player.damage_inflicted_by = vec![]);
+    lobby.engine.apply_actions(&player_actions);
 
-    calculate_projectile_updates(game_state);
+    ping_clients_with_new_tick(lobby, server_arc.clone(), db_arc.clone());
 
-    ping_clients_with_new_tick(lobby, db_arc.clone());
+    let game_state_out = get_game_state_out(lobby);
+    replay::record_tick(lobby, tick_input, game_state_out);
 
     if lobby.status == models::LobbyStatus::RUNNING {
         tokio::spawn(schedule_next_client_update(
-            lobby.tick,
+            lobby.engine.tick(),
             lobby_id,
             server_arc.clone(),
             db_arc.clone(),
         ));
+    } else {
+        replay::stop_recording(lobby);
     }
 }
 
-fn ping_clients_with_new_tick(lobby: &mut models::Lobby, db_arc: models::DbArc) {
-    lobby.tick = Uuid::new_v4();
-    lobby.round += 1;
+/// Informs all clients about the game state of the current tick. Stops the lobby if the engine
+/// reports that the game is over.
+fn ping_clients_with_new_tick(
+    lobby: &mut models::Lobby,
+    server_arc: models::ServerArc,
+    db_arc: models::DbArc,
+) {
+    lobby.client_messages = HashMap::new();
 
-    if lobby.round >= MAX_ROUNDS {
-        info!(
-            "Maximum of rounds ({}) was reached, stopping lobby.",
-            MAX_ROUNDS
-        );
-        lobby.status = models::LobbyStatus::FINISHED;
-        return;
+    let finish_reason = if lobby.status == models::LobbyStatus::RUNNING {
+        lobby.engine.finish_reason()
+    } else {
+        None
+    };
+
+    match finish_reason {
+        Some(engine::FinishReason::MAX_ROUNDS_REACHED) => {
+            info!(
+                "Maximum of rounds ({}) was reached, stopping lobby.",
+                lobby.engine.config().max_rounds
+            );
+            finish_game(
+                lobby,
+                GameOverReason::MAX_ROUNDS_REACHED,
+                false,
+                server_arc.clone(),
+                db_arc.clone(),
+            );
+        }
+        Some(engine::FinishReason::LAST_PLAYER_STANDING) => {
+            info!("1 or less players alive, stopping lobby");
+            finish_game(
+                lobby,
+                GameOverReason::LAST_PLAYER_STANDING,
+                true,
+                server_arc.clone(),
+                db_arc.clone(),
+            );
+        }
+        None => {
+            push_game_state_to_everyone(lobby, db_arc.clone());
+        }
     }
+}
 
-    lobby.client_messages = HashMap::new();
+/// Finishes the lobby and informs all clients that the game is over. The result is stored on the
+/// lobby, as the engine may still change afterwards. Games stopped by an admin are not rated.
+pub fn finish_game(
+    lobby: &mut models::Lobby,
+    reason: GameOverReason,
+    include_game_state: bool,
+    server_arc: models::ServerArc,
+    db_arc: models::DbArc,
+) {
+    let game_over = lobby.engine.game_over(reason);
 
-    push_game_state_to_everyone(lobby, db_arc.clone());
+    push_game_over_to_everyone(lobby, &game_over, include_game_state, db_arc.clone());
 
-    if get_amount_of_players_alive(lobby) <= 1 && get_player_count(lobby) > 1 {
-        info!("1 or less players alive, stopping lobby");
-        lobby.status = models::LobbyStatus::FINISHED;
-        return;
+    if reason != GameOverReason::STOPPED_BY_ADMIN {
+        tokio::spawn(ratings::record_finished_game(
+            lobby.id,
+            game_over.standings.clone(),
+            server_arc.clone(),
+        ));
     }
-}
 
-fn get_player_count(lobby: &mut models::Lobby) -> usize {
-    return lobby.game_state.players.values().count();
+    lobby.result = Some(game_over);
+    lobby.status = models::LobbyStatus::FINISHED;
 }
 
-fn get_amount_of_players_alive(lobby: &mut models::Lobby) -> usize {
-    return lobby
-        .game_state
-        .players
-        .values()
-        .filter(|player| player.health > 0)
-        .count();
+/// Informs all clients that the game is over. If requested, the final game state is sent in the
+/// same batch, so clients receive it before the game over message.
+fn push_game_over_to_everyone(
+    lobby: &mut models::Lobby,
+    game_over: &GameOver,
+    include_game_state: bool,
+    db_arc: models::DbArc,
+) {
+    let mut messages = vec![];
+
+    if include_game_state {
+        messages.push(ServerMessage::GAME_STATE(get_game_state_out(lobby)));
+    }
+
+    messages.push(ServerMessage::GAME_OVER(game_over.clone()));
+
+    info!(
+        "Pushing game over ({:?}) to clients of lobby with id '{}'",
+        game_over.reason, lobby.id
+    );
+
+    for (addr, client) in lobby.clients.iter() {
+        tokio::spawn(client_handling::send_messages_to_addr(
+            *addr,
+            messages
+                .iter()
+                .map(|message| {
+                    tokio_tungstenite::tungstenite::Message::Text(
+                        message.to_json(client.protocol_version),
+                    )
+                })
+                .collect(),
+            db_arc.clone(),
+        ));
+    }
 }
 
-fn push_game_state_to_everyone(lobby: &mut models::Lobby, db_arc: models::DbArc) {
+pub fn push_game_state_to_everyone(lobby: &mut models::Lobby, db_arc: models::DbArc) {
     let socket_addresses: Vec<SocketAddr> = lobby.clients.keys().cloned().collect();
 
     push_game_state_to_addresses(lobby, socket_addresses, db_arc.clone());
@@ -267,28 +248,34 @@ fn push_game_state_to_addresses(
     socket_addresses: Vec<SocketAddr>,
     db_arc: models::DbArc,
 ) {
-    let game_state_out = get_game_state_out(lobby);
+    let game_state_out = ServerMessage::GAME_STATE(get_game_state_out(lobby));
 
     for addr in socket_addresses {
-        push_game_state_to_address(addr, &game_state_out, db_arc.clone());
+        let protocol_version = match lobby.clients.get(&addr) {
+            Some(client) => client.protocol_version,
+            None => continue,
+        };
+
+        push_game_state_to_address(addr, &game_state_out, protocol_version, db_arc.clone());
     }
 }
 
 fn push_game_state_to_address(
     addr: SocketAddr,
-    game_state_out: &api_models::GameStateOut,
+    game_state_out: &ServerMessage,
+    protocol_version: u32,
     db_arc: models::DbArc,
 ) {
-    info!("Pushing game state for tick '{}'", game_state_out.tick);
-    let game_state_string = serde_json::to_string(game_state_out).unwrap();
+    info!("Pushing game state to client with address '{}'", addr);
+    let game_state_string = game_state_out.to_json(protocol_version);
     tokio::spawn(client_handling::send_message_to_addr(
-        addr.clone(),
+        addr,
         tokio_tungstenite::tungstenite::Message::Text(game_state_string),
         db_arc.clone(),
     ));
 }
 
-fn get_game_state_out(lobby: &mut models::Lobby) -> api_models::GameStateOut {
+pub fn get_game_state_out(lobby: &mut models::Lobby) -> api_models::GameStateOut {
     let spectator_count = lobby
         .clients
         .values()
@@ -297,14 +284,13 @@ fn get_game_state_out(lobby: &mut models::Lobby) -> api_models::GameStateOut {
         .try_into()
         .unwrap();
 
-    let game_state = lobby.game_state.clone();
-    return api_models::GameStateOut {
-        tick: lobby.tick,
-        tick_length_milli_seconds: lobby.tick_length_milli_seconds,
-        spectators: spectator_count,
-        entities: game_state.entities,
-        players: transform_map_of_players_to_list_of_player(game_state.players),
-    };
+    if let Some(playback) = &lobby.playback {
+        let mut game_state_out = replay::get_current_frame(playback).game_state.clone();
+        game_state_out.spectators = spectator_count;
+        return game_state_out;
+    }
+
+    return lobby.engine.game_state_out(spectator_count);
 }
 
 async fn schedule_next_client_update(
@@ -345,7 +331,13 @@ async fn run_deffered_client_update(
     server_arc: models::ServerArc,
     db_arc: models::DbArc,
 ) {
-    time::sleep(Duration::from_millis(GAME_TICK_LENGTH)).await;
+    let tick_length_milli_seconds = {
+        let server = server_arc.lock().await;
+        let lobby = server.lobbies.get(&lobby_id).unwrap();
+        lobby.engine.config().tick_length_milli_seconds
+    };
+
+    time::sleep(Duration::from_millis(tick_length_milli_seconds)).await;
 
     let mut server = server_arc.lock().await;
     let lobby = server.lobbies.get_mut(&lobby_id).unwrap();
@@ -360,73 +352,92 @@ async fn run_deffered_client_update(
     .await;
 }
 
-fn transform_map_of_players_to_list_of_player(
-    map_of_player: HashMap<SocketAddr, Player>,
-) -> Vec<Player> {
-    return map_of_player.values().cloned().collect();
-}
-
 pub async fn handle_client_connect(
     lobby: &mut models::Lobby,
     addr: SocketAddr,
     new_client: models::Client,
     db_arc: models::DbArc,
 ) -> Result<Option<api_models::ClientHello>, String> {
-    lobby.clients.insert(addr, new_client.clone());
-
     if new_client.client_type == models::ClientType::PLAYER {
-        let player_id = Uuid::new_v4();
-        let player_count = get_player_count(lobby);
-        let color = PLAYER_COUNT_TO_COLOR.get(&(player_count + 1));
-
-        if color.is_none() {
-            return Err(format!(
-                "Could not get color for new player. Lobby already has {} players.",
-                player_count
-            ));
-        };
+        let player_id = lobby.engine.add_player(new_client.username.clone())?;
+        let session_token = Uuid::new_v4().simple().to_string();
+
+        lobby.clients.insert(addr, new_client.clone());
+        lobby.players.insert(addr, player_id);
+        lobby.sessions.insert(
+            session_token.clone(),
+            models::PlayerSession {
+                player_id,
+                username: new_client.username.clone(),
+                addr: Some(addr),
+                disconnected_at: None,
+            },
+        );
 
-        let new_player = models::Player {
-            entity_type: models::EntityType::PLAYER,
-            damage_inflicted_by: vec![],
-            id: player_id,
-            name: new_client.username.clone(),
-            x: 0,
-            y: 0,
-            rotation: 100,
-            color: color.unwrap().to_string(),
-            health: 100,
-            last_action_success: true,
-            error_message: "".to_string(),
-        };
+        push_game_state_to_spectators(lobby, db_arc.clone());
 
-        lobby.game_state.players.insert(addr, new_player);
+        return Ok(Some(api_models::ClientHello {
+            success: true,
+            player_id,
+            message: "Connection successful.".to_string(),
+            session_token: Some(session_token),
+        }));
+    }
 
-        let client_hello = match update_initial_player_positions(lobby) {
-            Ok(()) => {
-                push_game_state_to_spectators(lobby, db_arc.clone());
-                api_models::ClientHello {
-                    success: true,
-                    player_id: player_id,
-                    message: "Connection successful.".to_string(),
-                }
-            }
+    lobby.clients.insert(addr, new_client.clone());
 
-            Err(error_message) => {
-                return Err(error_message);
-            }
-        };
+    push_game_state_to_spectators(lobby, db_arc.clone());
+    return Ok(None);
+}
+
+/// Reattaches the player of a disconnected session to a new connection.
+pub fn handle_client_reconnect(
+    lobby: &mut models::Lobby,
+    addr: SocketAddr,
+    session_token: &str,
+    protocol_version: u32,
+) -> Result<api_models::ClientHello, String> {
+    let session = match lobby.sessions.get_mut(session_token) {
+        Some(session) => session,
+        None => return Err("Session token is unknown or has expired".to_string()),
+    };
 
-        return Ok(Some(client_hello));
+    if session.addr.is_some() {
+        return Err("Session is still connected".to_string());
     }
 
-    push_game_state_to_spectators(lobby, db_arc.clone());
-    return Ok(None);
+    session.addr = Some(addr);
+    session.disconnected_at = None;
+
+    let player_id = session.player_id;
+
+    lobby.clients.insert(
+        addr,
+        models::Client {
+            client_type: models::ClientType::PLAYER,
+            username: session.username.clone(),
+            protocol_version,
+        },
+    );
+    lobby.players.insert(addr, player_id);
+
+    info!(
+        "Player with id '{}' reconnected from address '{}'",
+        player_id, addr
+    );
+
+    return Ok(api_models::ClientHello {
+        success: true,
+        player_id,
+        message: "Reconnected successfully.".to_string(),
+        session_token: Some(session_token.to_string()),
+    });
 }
 
 pub fn handle_client_disconnect(
     lobby: &mut models::Lobby,
     addr: SocketAddr,
+    server_arc: models::ServerArc,
     db_arc: models::DbArc,
 ) {
     let client_type = lobby.clients.get(&addr).unwrap().client_type.clone();
@@ -436,106 +447,138 @@ pub fn handle_client_disconnect(
     info!("Client of type {:?} disconnected", client_type);
 
     if client_type == models::ClientType::PLAYER {
-        lobby.game_state.players.remove(&addr);
+        let player_id_option = lobby.players.remove(&addr);
+
+        // The result of a finished game must not change, so the player stays in the engine
+        if lobby.status == models::LobbyStatus::FINISHED {
+            if let Some(player_id) = player_id_option {
+                lobby
+                    .sessions
+                    .retain(|_, session| session.player_id != player_id);
+            }
 
-        if lobby.status == models::LobbyStatus::PENDING {
-            let _ = update_initial_player_positions(lobby);
+            return;
         }
 
-        ping_clients_with_new_tick(lobby, db_arc.clone());
+        if let Some(player_id) = player_id_option {
+            if lobby.status == models::LobbyStatus::RUNNING {
+                let session_option = lobby
+                    .sessions
+                    .iter_mut()
+                    .find(|(_, session)| session.player_id == player_id);
+
+                if let Some((session_token, session)) = session_option {
+                    let disconnected_at = Instant::now();
+
+                    session.addr = None;
+                    session.disconnected_at = Some(disconnected_at);
+
+                    info!(
+                        "Keeping player with id '{}' in game, until reconnect grace period expires",
+                        player_id
+                    );
+
+                    tokio::spawn(expire_session(
+                        lobby.id,
+                        session_token.clone(),
+                        disconnected_at,
+                        server_arc.clone(),
+                        db_arc.clone(),
+                    ));
+
+                    return;
+                }
+            }
+
+            lobby
+                .sessions
+                .retain(|_, session| session.player_id != player_id);
+        }
+
+        remove_player_from_game(lobby, player_id_option, server_arc.clone(), db_arc.clone());
     } else {
         push_game_state_to_spectators(lobby, db_arc.clone());
     }
 }
 
-fn handle_client_message(
-    client_message: api_models::ClientMessage,
-    addr: &SocketAddr,
-    game_state: &mut models::GameState,
+/// Removes the player from a pending game. Players leaving a running game are eliminated
+/// instead, so they keep their place in the standings.
+fn remove_player_from_game(
+    lobby: &mut models::Lobby,
+    player_id_option: Option<Uuid>,
+    server_arc: models::ServerArc,
+    db_arc: models::DbArc,
 ) {
-    let player = game_state.players.get_mut(addr).unwrap();
+    if let Some(player_id) = player_id_option {
+        replay::record_disconnect(lobby, player_id);
 
-    player.error_message = "".to_string();
-    player.last_action_success = true;
+        if lobby.status == models::LobbyStatus::RUNNING {
+            lobby.engine.eliminate_player(&player_id);
+        } else {
+            lobby.engine.remove_player(&player_id);
+        }
+    }
 
-    if player.health <= 0 {
-        player.last_action_success = false;
-        player.error_message =
-            "Message was not processed, because player has no more health left".to_string();
-        return;
+    if lobby.status == models::LobbyStatus::PENDING {
+        let _ = lobby.engine.assign_starting_positions();
     }
 
-    match client_message.action {
-        api_models::ClientAction::SHOOT => {
-            let new_projectile = models::Projectile {
-                travel_distance: PROJECTILE_UNIT_LENGTH_TRAVEL_DISTANCE,
-                id: Uuid::new_v4(),
-                previous_x: player.x.into(),
-                previous_y: player.y.into(),
-                x: player.x.into(),
-                y: player.y.into(),
-                direction: player.rotation,
-                source: player.id,
-            };
-
-            game_state.entities.push(new_projectile);
-        }
-        api_models::ClientAction::TURN => {
-            if client_message.degrees.is_none() {
-                player.error_message =
-                    "Cannot TURN, because no 'degrees' property was supplied".to_string();
-                player.last_action_success = false;
-                return;
-            }
+    lobby.engine.advance_tick();
 
-            let degrees = client_message.degrees.unwrap();
+    ping_clients_with_new_tick(lobby, server_arc.clone(), db_arc.clone());
 
-            if !(degrees >= 0 && degrees <= 360) {
-                player.error_message =
-                    "Cannot TURN, because 'degrees' is not within range (0 - 360)".to_string();
-                player.last_action_success = false;
-                return;
-            }
+    if lobby.status == models::LobbyStatus::FINISHED {
+        replay::stop_recording(lobby);
+    }
+}
 
-            player.rotation = degrees;
-        }
-        api_models::ClientAction::UP => {
-            if player.y < MAX_FIELD_SIZE_Y - 1 {
-                player.y += 1;
-            } else {
-                player.error_message =
-                    "Cannot move UP, because player is at border of field".to_string();
-                player.last_action_success = false;
-            }
-        }
-        api_models::ClientAction::DOWN => {
-            if player.y > 0 {
-                player.y -= 1;
-            } else {
-                player.error_message =
-                    "Cannot move DOWN, because player is at border of field".to_string();
-                player.last_action_success = false;
-            }
-        }
-        api_models::ClientAction::RIGHT => {
-            if player.x < MAX_FIELD_SIZE_X - 1 {
-                player.x += 1;
-            } else {
-                player.error_message =
-                    "Cannot move RIGHT, because player is at border of field".to_string();
-                player.last_action_success = false;
-            }
-        }
-        api_models::ClientAction::LEFT => {
-            if player.x > 0 {
-                player.x -= 1;
-            } else {
-                player.error_message =
-                    "Cannot move LEFT, because player is at border of field".to_string();
-                player.last_action_success = false;
-            }
-        }
+fn get_reconnect_grace_period() -> Duration {
+    let milli_seconds = env::var("RECONNECT_GRACE_PERIOD_MILLI_SECONDS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_RECONNECT_GRACE_PERIOD_MILLI_SECONDS);
+
+    return Duration::from_millis(milli_seconds);
+}
+
+async fn expire_session(
+    lobby_id: Uuid,
+    session_token: String,
+    disconnected_at: Instant,
+    server_arc: models::ServerArc,
+    db_arc: models::DbArc,
+) {
+    time::sleep(get_reconnect_grace_period()).await;
+
+    let mut server = server_arc.lock().await;
+
+    let lobby = match server.lobbies.get_mut(&lobby_id) {
+        Some(lobby) => lobby,
+        None => return,
+    };
+
+    let player_id = match lobby.sessions.get(&session_token) {
+        Some(session) if session.disconnected_at == Some(disconnected_at) => session.player_id,
+        _ => return,
+    };
+
+    lobby.sessions.remove(&session_token);
+
+    // Once the game is over, the player stays in the engine, so the result does not change
+    if lobby.status != models::LobbyStatus::RUNNING {
+        info!(
+            "Session of player with id '{}' expired after the game ended",
+            player_id
+        );
+        return;
     }
+
+    info!(
+        "Player with id '{}' did not reconnect in time, removing player from lobby",
+        player_id
+    );
+
+    remove_player_from_game(lobby, Some(player_id), server_arc.clone(), db_arc.clone());
 }
 
 pub async fn check_all_clients_responded(
@@ -564,7 +607,7 @@ pub async fn check_all_clients_responded(
     if clients_that_answered.is_superset(&expected_client_addresses) {
         let mut db = db_arc.lock().await;
 
-        let expected_tick = lobby.tick;
+        let expected_tick = lobby.engine.tick();
 
         info!(
             "Triggering premature lobby update, because all clients responded for tick '{}'",
@@ -592,74 +635,3 @@ pub async fn check_all_clients_responded(
         .await;
     }
 }
-
-fn calculate_projectile_updates(game_state: &mut models::GameState) {
-    game_state.entities = game_state
-        .entities
-        .iter()
-        .cloned()
-        .filter(|projectile| {
-            if projectile.x < 0.0 {
-                return false;
-            }
-            if projectile.y < 0.0 {
-                return false;
-            }
-            if projectile.x > MAX_FIELD_SIZE_X.into() {
-                return false;
-            }
-            if projectile.y > MAX_FIELD_SIZE_Y.into() {
-                return false;
-            }
-            return true;
-        })
-        .collect();
-
-    game_state.entities.iter_mut().for_each(|projectile| {
-        let list_of_hit_coordinates = get_fields_passed_by_projectile(projectile);
-
-        game_state.players.values_mut().for_each(|player| {
-            if list_of_hit_coordinates.contains(&(player.x, player.y))
-                && projectile.source != player.id
-            {
-                player.health = std::cmp::max(0, player.health - 20);
-
-                player.damage_inflicted_by.push(projectile.source);
-                return;
-            }
-        });
-
-        let ending_coordinates =
-            get_ending_coordinates_of_projectile(projectile.x, projectile.y, projectile.direction);
-
-        projectile.previous_x = projectile.x;
-        projectile.previous_y = projectile.y;
-        projectile.x = ending_coordinates.0;
-        projectile.y = ending_coordinates.1;
-    })
-}
-
-fn get_fields_passed_by_projectile(projectile: &models::Projectile) -> Vec<(i32, i32)> {
-    let start_point: line_drawing::Point<f64> = (projectile.x, projectile.y);
-
-    let end_point: line_drawing::Point<f64> =
-        get_ending_coordinates_of_projectile(projectile.x, projectile.y, projectile.direction);
-    return line_drawing::Midpoint::new(start_point, end_point).collect();
-}
-
-fn get_ending_coordinates_of_projectile(start_x: f64, start_y: f64, direction: i32) -> (f64, f64) {
-    let directional_vector = get_directional_vector_from_degrees(direction);
-
-    let end_x = start_x + PROJECTILE_UNIT_LENGTH_TRAVEL_DISTANCE * directional_vector.0;
-    let end_y = start_y + PROJECTILE_UNIT_LENGTH_TRAVEL_DISTANCE * directional_vector.1;
-
-    return (end_x, end_y);
-}
-
-fn get_directional_vector_from_degrees(degrees: i32) -> (f64, f64) {
-    let degrees_f64: f64 = degrees.into();
-
-    let radians = ((90.0 - degrees_f64) * PI) / 180.0;
-
-    return (f64::cos(radians), f64::sin(radians));
-}
@@ -1,3 +1,5 @@
+#![allow(clippy::needless_return, clippy::upper_case_acronyms)]
+
 use std::borrow::Cow;
 use std::collections::HashMap;
 
@@ -5,6 +7,8 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::{env, io::Error};
 
+use engine::protocol::{ServerMessage, LEGACY_PROTOCOL_VERSION, SUPPORTED_PROTOCOL_VERSIONS};
+use engine::{GameConfig, GameEngine};
 use futures_util::{pin_mut, SinkExt, StreamExt};
 use models::Connection;
 use tokio::net::TcpListener;
@@ -14,16 +18,21 @@ use tokio_tungstenite::tungstenite::protocol::CloseFrame;
 use tokio_tungstenite::tungstenite::Message;
 
 use log::info;
-use querystring;
 use regex::Regex;
 
 use uuid::Uuid;
 
 mod api_models;
+mod auth;
 mod client_handling;
 mod game;
 mod management_api;
+mod maps;
 mod models;
+mod ratings;
+mod replay;
+mod teams;
+mod tournament;
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
@@ -38,26 +47,26 @@ async fn main() -> Result<(), Error> {
 
     info!("Listening on: {}:8080", addr);
 
+    let team_registry = match teams::TeamRegistry::from_env() {
+        Ok(team_registry) => Arc::new(team_registry),
+        Err(error_message) => panic!("{}", error_message),
+    };
+
+    let ratings = match ratings::RatingStore::from_env(team_registry.is_enabled()) {
+        Ok(ratings) => ratings,
+        Err(error_message) => panic!("{}", error_message),
+    };
+
     let mut server = models::Server {
         lobbies: HashMap::new(),
+        tournaments: HashMap::new(),
+        ratings,
     };
 
     // let lobby_id = Uuid::new_v4();
     let lobby_id = Uuid::parse_str("9ec2a984-b5bf-4a13-89fd-53c0d9cafef6").unwrap();
 
-    let lobby = models::Lobby {
-        round: 0,
-        tick: Uuid::new_v4(),
-        tick_length_milli_seconds: game::GAME_TICK_LENGTH,
-        client_messages: HashMap::new(),
-        clients: HashMap::new(),
-        id: lobby_id,
-        status: models::LobbyStatus::PENDING,
-        game_state: models::GameState {
-            players: HashMap::new(),
-            entities: Vec::new(),
-        },
-    };
+    let lobby = models::Lobby::new(lobby_id, GameEngine::new(GameConfig::default()));
 
     info!("Lobby created with id: {}", lobby.id);
 
@@ -76,6 +85,7 @@ async fn main() -> Result<(), Error> {
         listener,
         db_arc.clone(),
         server_arc.clone(),
+        team_registry.clone(),
     ));
 
     let address_ip_parts: [u8; 4] = addr
@@ -85,9 +95,18 @@ async fn main() -> Result<(), Error> {
         .try_into()
         .unwrap();
 
+    let auth_arc = Arc::new(auth::ManagementApiAuth::from_env());
+
+    let map_registry = match maps::MapRegistry::from_env() {
+        Ok(map_registry) => Arc::new(map_registry),
+        Err(error_message) => panic!("{}", error_message),
+    };
+
     let rest_api = warp::serve(management_api::management_api(
         server_arc.clone(),
         db_arc.clone(),
+        auth_arc.clone(),
+        map_registry.clone(),
     ))
     .run((address_ip_parts, 8081));
     pin_mut!(rest_api);
@@ -100,7 +119,10 @@ async fn listen_for_connections(
     listener: TcpListener,
     db_arc: models::DbArc,
     server_arc: models::ServerArc,
+    team_registry: Arc<teams::TeamRegistry>,
 ) {
+    let request_regex = Regex::new(r"^\/lobby\/(.*)\?(.*)").unwrap();
+
     while let Ok((stream, _)) = listener.accept().await {
         info!("New connection incoming");
         let addr = stream
@@ -133,12 +155,10 @@ async fn listen_for_connections(
 
         let request_url = request_parts.get(1).expect("Could not get URL of request");
 
-        let request_regex = Regex::new(r"^\/lobby\/(.*)\?(.*)").unwrap();
-
         let mut results = vec![];
 
         for (_, [lobby_id, query_string]) in request_regex
-            .captures_iter(&request_url)
+            .captures_iter(request_url)
             .map(|c| c.extract())
         {
             results.push(lobby_id);
@@ -147,7 +167,7 @@ async fn listen_for_connections(
 
         info!("regex matches: {:?}", results);
 
-        let lobby_id_str_option = results.get(0);
+        let lobby_id_str_option = results.first();
 
         if lobby_id_str_option.is_none() {
             close_connection(
@@ -203,7 +223,7 @@ async fn listen_for_connections(
 
         let client_type_str = client_type_str_option.unwrap();
 
-        let client_type_result = models::ClientType::from_str(&client_type_str);
+        let client_type_result = models::ClientType::from_str(client_type_str);
         if client_type_result.is_err() {
             close_connection(
                 &mut new_connection,
@@ -215,9 +235,55 @@ async fn listen_for_connections(
 
         let client_type = client_type_result.unwrap();
 
-        let username = query_params.get("username").unwrap_or(&"");
+        let protocol_version = match query_params.get("protocolVersion") {
+            Some(protocol_version_str) => match protocol_version_str.parse::<u32>() {
+                Ok(protocol_version) if SUPPORTED_PROTOCOL_VERSIONS.contains(&protocol_version) => {
+                    protocol_version
+                }
+                _ => {
+                    close_connection(
+                        &mut new_connection,
+                        format!(
+                            "Protocol version '{}' is not supported. Supported versions: {:?}",
+                            protocol_version_str, SUPPORTED_PROTOCOL_VERSIONS
+                        ),
+                    )
+                    .await;
+                    continue;
+                }
+            },
+            None => LEGACY_PROTOCOL_VERSION,
+        };
+
+        let mut username = query_params.get("username").unwrap_or(&"").to_string();
+        let session_token_option = query_params.get("sessionToken");
+
+        let requires_team_token = client_type == models::ClientType::PLAYER
+            && session_token_option.is_none()
+            && team_registry.is_enabled();
+
+        if requires_team_token {
+            let team_option = query_params
+                .get("token")
+                .and_then(|token| team_registry.find_team(token));
+
+            if team_option.is_none() {
+                close_connection(
+                    &mut new_connection,
+                    "Player clients must supply a valid team 'token' via the query parameter"
+                        .to_string(),
+                )
+                .await;
+                continue;
+            }
+
+            username = team_option.unwrap().name.clone();
+        }
 
-        if client_type == models::ClientType::PLAYER && username.to_string() == "" {
+        if client_type == models::ClientType::PLAYER
+            && username.is_empty()
+            && session_token_option.is_none()
+        {
             close_connection(
                 &mut new_connection,
                 "Player clients must supply a 'username' via the query parameter".to_string(),
@@ -234,8 +300,9 @@ async fn listen_for_connections(
         info!("New WebSocket connection: {}", addr);
 
         let new_client = models::Client {
-            client_type: client_type,
-            username: username.to_string(),
+            client_type,
+            username,
+            protocol_version,
         };
 
         let mut server = server_arc.lock().await;
@@ -253,6 +320,91 @@ async fn listen_for_connections(
 
         let lobby = lobby_option.unwrap();
 
+        if lobby.playback.is_some() && new_client.client_type == models::ClientType::PLAYER {
+            close_connection(
+                &mut new_connection,
+                format!(
+                    "Lobby with id '{}' is a replay lobby and only accepts spectators",
+                    lobby_id_str
+                ),
+            )
+            .await;
+            continue;
+        }
+
+        if let (models::ClientType::PLAYER, Some(session_token)) =
+            (&new_client.client_type, session_token_option)
+        {
+            match game::handle_client_reconnect(lobby, addr, session_token, protocol_version) {
+                Ok(client_hello) => {
+                    let mut db = db_arc.lock().await;
+                    db.connections.insert(addr, new_connection);
+
+                    let game_state_out = game::get_game_state_out(lobby);
+                    let messages = vec![
+                        Message::Text(
+                            ServerMessage::CLIENT_HELLO(client_hello).to_json(protocol_version),
+                        ),
+                        Message::Text(
+                            ServerMessage::GAME_STATE(game_state_out).to_json(protocol_version),
+                        ),
+                    ];
+
+                    tokio::spawn(client_handling::send_messages_to_addr(
+                        addr,
+                        messages,
+                        db_arc.clone(),
+                    ));
+
+                    tokio::spawn(client_handling::listen_for_messages(
+                        read,
+                        addr,
+                        lobby_uuid,
+                        db_arc.clone(),
+                        server_arc.clone(),
+                    ));
+                }
+                Err(error_message) => {
+                    close_connection(&mut new_connection, error_message).await;
+                }
+            }
+            continue;
+        }
+
+        if let Some(reserved_for) = &lobby.reserved_for {
+            if new_client.client_type == models::ClientType::PLAYER
+                && session_token_option.is_none()
+                && !reserved_for.contains(&new_client.username)
+            {
+                close_connection(
+                    &mut new_connection,
+                    format!(
+                        "Lobby with id '{}' is reserved for the players {:?}",
+                        lobby_id_str, reserved_for
+                    ),
+                )
+                .await;
+                continue;
+            }
+        }
+
+        if (requires_team_token || lobby.reserved_for.is_some())
+            && lobby
+                .sessions
+                .values()
+                .any(|session| session.username == new_client.username)
+        {
+            close_connection(
+                &mut new_connection,
+                format!(
+                    "Player '{}' is already connected to lobby with id '{}'",
+                    new_client.username, lobby_id_str
+                ),
+            )
+            .await;
+            continue;
+        }
+
         if matches!(new_client.client_type, models::ClientType::SPECTATOR)
             || (matches!(new_client.client_type, models::ClientType::PLAYER)
                 && matches!(lobby.status, models::LobbyStatus::PENDING))
@@ -264,8 +416,10 @@ async fn listen_for_connections(
                 Ok(client_hello) => {
                     db.connections.insert(addr, new_connection);
 
-                    if new_client.client_type == models::ClientType::PLAYER {
-                        let message = Message::Text(serde_json::to_string(&client_hello).unwrap());
+                    if let Some(client_hello) = client_hello {
+                        let message = Message::Text(
+                            ServerMessage::CLIENT_HELLO(client_hello).to_json(protocol_version),
+                        );
 
                         tokio::spawn(client_handling::send_message_to_addr(
                             addr,
@@ -1,15 +1,23 @@
+use engine::protocol::GameOverReason;
+use engine::GameEngine;
 use log::info;
 use uuid::Uuid;
 use warp::http::StatusCode;
+use warp::hyper::body::Bytes;
+use warp::Reply;
 
-use std::collections::HashMap;
 use std::convert::Infallible;
+use std::sync::Arc;
 
 use warp::Filter;
 
 use crate::api_models;
+use crate::auth;
 use crate::game;
+use crate::maps;
 use crate::models;
+use crate::replay;
+use crate::tournament;
 
 fn with_server(
     server_arc: models::ServerArc,
@@ -23,50 +31,237 @@ fn with_db(
     warp::any().map(move || db_arc.clone())
 }
 
+fn with_maps(
+    map_registry: Arc<maps::MapRegistry>,
+) -> impl Filter<Extract = (Arc<maps::MapRegistry>,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || map_registry.clone())
+}
+
+fn with_access(
+    auth_arc: Arc<auth::ManagementApiAuth>,
+    access: auth::Access,
+) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>("authorization")
+        .and_then(move |authorization: Option<String>| {
+            let auth_arc = auth_arc.clone();
+            async move {
+                if auth_arc.is_authorized(authorization.as_deref(), access) {
+                    return Ok(());
+                }
+                return Err(warp::reject::custom(auth::Unauthorized));
+            }
+        })
+        .untuple_one()
+}
+
 pub fn management_api(
     server_arc: models::ServerArc,
     db_arc: models::DbArc,
+    auth_arc: Arc<auth::ManagementApiAuth>,
+    map_registry: Arc<maps::MapRegistry>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     let cors = warp::cors()
         .allow_any_origin()
         .allow_methods(vec!["GET", "POST", "PATCH"])
         .allow_headers(vec!["Content-Type", "Authorization"]);
-    list_lobbies(server_arc.clone())
-        .or(create_lobby(server_arc.clone()))
-        .or(update_lobby(server_arc.clone(), db_arc.clone()))
+    list_lobbies(server_arc.clone(), auth_arc.clone())
+        .or(create_lobby(
+            server_arc.clone(),
+            auth_arc.clone(),
+            map_registry.clone(),
+        ))
+        .or(update_lobby(
+            server_arc.clone(),
+            db_arc.clone(),
+            auth_arc.clone(),
+        ))
+        .or(list_replays(auth_arc.clone()))
+        .or(verify_replay(auth_arc.clone()))
+        .or(create_replay_lobby(server_arc.clone(), auth_arc.clone()))
+        .or(update_replay_lobby(server_arc.clone(), auth_arc.clone()))
+        .or(list_tournaments(server_arc.clone(), auth_arc.clone()))
+        .or(get_tournament(server_arc.clone(), auth_arc.clone()))
+        .or(create_tournament(
+            server_arc.clone(),
+            db_arc.clone(),
+            auth_arc.clone(),
+            map_registry.clone(),
+        ))
+        .or(get_leaderboard(server_arc.clone(), auth_arc.clone()))
+        .or(get_player_history(server_arc.clone(), auth_arc.clone()))
+        .or(list_maps(auth_arc.clone(), map_registry.clone()))
+        .recover(handle_rejection)
         .with(cors)
 }
 
+async fn handle_rejection(rejection: warp::Rejection) -> Result<impl warp::Reply, warp::Rejection> {
+    if rejection.find::<auth::Unauthorized>().is_some() {
+        return Ok(warp::reply::with_header(
+            warp::reply::with_status(
+                "Missing or invalid API key. Supply it via the 'Authorization: Bearer <key>' header."
+                    .to_string(),
+                StatusCode::UNAUTHORIZED,
+            ),
+            "WWW-Authenticate",
+            "Bearer",
+        ));
+    }
+
+    return Err(rejection);
+}
+
 fn list_lobbies(
     server_arc: models::ServerArc,
+    auth_arc: Arc<auth::ManagementApiAuth>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("lobbies")
         .and(warp::get())
+        .and(with_access(auth_arc, auth::Access::READ))
         .and(with_server(server_arc.clone()))
         .and_then(get_lobbies_list_reply)
 }
 
 fn create_lobby(
     server_arc: models::ServerArc,
+    auth_arc: Arc<auth::ManagementApiAuth>,
+    map_registry: Arc<maps::MapRegistry>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("lobbies")
         .and(warp::post())
+        .and(with_access(auth_arc, auth::Access::WRITE))
         .and(with_server(server_arc.clone()))
+        .and(with_maps(map_registry))
+        .and(warp::body::bytes())
         .and_then(get_create_lobby_reply)
 }
 
 fn update_lobby(
     server_arc: models::ServerArc,
     db_arc: models::DbArc,
+    auth_arc: Arc<auth::ManagementApiAuth>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("lobbies" / Uuid)
         .and(warp::patch())
+        .and(with_access(auth_arc, auth::Access::WRITE))
         .and(with_server(server_arc.clone()))
         .and(with_db(db_arc.clone()))
         .and(warp::body::json())
         .and_then(get_update_lobby_reply)
 }
 
+fn list_replays(
+    auth_arc: Arc<auth::ManagementApiAuth>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("replays")
+        .and(warp::get())
+        .and(with_access(auth_arc, auth::Access::READ))
+        .and_then(get_replays_list_reply)
+}
+
+fn verify_replay(
+    auth_arc: Arc<auth::ManagementApiAuth>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("replays" / String / "verification")
+        .and(warp::get())
+        .and(with_access(auth_arc, auth::Access::READ))
+        .and_then(get_replay_verification_reply)
+}
+
+fn create_replay_lobby(
+    server_arc: models::ServerArc,
+    auth_arc: Arc<auth::ManagementApiAuth>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("replays")
+        .and(warp::post())
+        .and(with_access(auth_arc, auth::Access::WRITE))
+        .and(with_server(server_arc.clone()))
+        .and(warp::body::json())
+        .and_then(get_create_replay_lobby_reply)
+}
+
+fn update_replay_lobby(
+    server_arc: models::ServerArc,
+    auth_arc: Arc<auth::ManagementApiAuth>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("replays" / Uuid)
+        .and(warp::patch())
+        .and(with_access(auth_arc, auth::Access::WRITE))
+        .and(with_server(server_arc.clone()))
+        .and(warp::body::json())
+        .and_then(get_update_replay_lobby_reply)
+}
+
+fn list_tournaments(
+    server_arc: models::ServerArc,
+    auth_arc: Arc<auth::ManagementApiAuth>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("tournaments")
+        .and(warp::get())
+        .and(with_access(auth_arc, auth::Access::READ))
+        .and(with_server(server_arc.clone()))
+        .and_then(get_tournaments_list_reply)
+}
+
+fn get_tournament(
+    server_arc: models::ServerArc,
+    auth_arc: Arc<auth::ManagementApiAuth>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("tournaments" / Uuid)
+        .and(warp::get())
+        .and(with_access(auth_arc, auth::Access::READ))
+        .and(with_server(server_arc.clone()))
+        .and_then(get_tournament_reply)
+}
+
+fn create_tournament(
+    server_arc: models::ServerArc,
+    db_arc: models::DbArc,
+    auth_arc: Arc<auth::ManagementApiAuth>,
+    map_registry: Arc<maps::MapRegistry>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("tournaments")
+        .and(warp::post())
+        .and(with_access(auth_arc, auth::Access::WRITE))
+        .and(with_server(server_arc.clone()))
+        .and(with_db(db_arc.clone()))
+        .and(with_maps(map_registry))
+        .and(warp::body::json())
+        .and_then(get_create_tournament_reply)
+}
+
+fn get_leaderboard(
+    server_arc: models::ServerArc,
+    auth_arc: Arc<auth::ManagementApiAuth>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("leaderboard")
+        .and(warp::get())
+        .and(with_access(auth_arc, auth::Access::READ))
+        .and(with_server(server_arc.clone()))
+        .and_then(get_leaderboard_reply)
+}
+
+fn get_player_history(
+    server_arc: models::ServerArc,
+    auth_arc: Arc<auth::ManagementApiAuth>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("players" / String / "history")
+        .and(warp::get())
+        .and(with_access(auth_arc, auth::Access::READ))
+        .and(with_server(server_arc.clone()))
+        .and_then(get_player_history_reply)
+}
+
+fn list_maps(
+    auth_arc: Arc<auth::ManagementApiAuth>,
+    map_registry: Arc<maps::MapRegistry>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("maps")
+        .and(warp::get())
+        .and(with_access(auth_arc, auth::Access::READ))
+        .and(with_maps(map_registry))
+        .and_then(get_maps_list_reply)
+}
+
 async fn get_update_lobby_reply(
     lobby_id: Uuid,
     server_arc: models::ServerArc,
@@ -85,11 +280,29 @@ async fn get_update_lobby_reply(
             }
 
             if update_lobby_body.status == models::LobbyStatus::RUNNING {
-                tokio::spawn(game::start_game_for_lobby(
-                    lobby_id,
+                if lobby.playback.is_some() {
+                    tokio::spawn(replay::start_playback(
+                        lobby_id,
+                        server_arc.clone(),
+                        db_arc.clone(),
+                    ));
+                } else {
+                    tokio::spawn(game::start_game_for_lobby(
+                        lobby_id,
+                        server_arc.clone(),
+                        db_arc.clone(),
+                    ));
+                }
+            }
+
+            if lobby.status == models::LobbyStatus::RUNNING && lobby.playback.is_none() {
+                game::finish_game(
+                    lobby,
+                    GameOverReason::STOPPED_BY_ADMIN,
+                    false,
                     server_arc.clone(),
                     db_arc.clone(),
-                ));
+                );
             }
 
             lobby.status = update_lobby_body.status.clone();
@@ -111,30 +324,49 @@ async fn get_update_lobby_reply(
 
 async fn get_create_lobby_reply(
     server_arc: models::ServerArc,
-) -> Result<impl warp::Reply, Infallible> {
+    map_registry: Arc<maps::MapRegistry>,
+    body: Bytes,
+) -> Result<warp::reply::Response, Infallible> {
+    let create_lobby_body = if body.is_empty() {
+        api_models::CreateLobbyBody::default()
+    } else {
+        match serde_json::from_slice::<api_models::CreateLobbyBody>(&body) {
+            Ok(create_lobby_body) => create_lobby_body,
+            Err(err) => {
+                return Ok(warp::reply::with_status(
+                    format!("Could not parse request body: {}", err),
+                    StatusCode::BAD_REQUEST,
+                )
+                .into_response());
+            }
+        }
+    };
+
+    let mut config = create_lobby_body.config;
+
+    // Generating a map may take a while, so the map is applied before the server is locked
+    if let Err(error_message) = config
+        .validate_limits()
+        .and_then(|_| map_registry.apply_map(&mut config))
+        .and_then(|_| config.validate())
+    {
+        return Ok(
+            warp::reply::with_status(error_message, StatusCode::UNPROCESSABLE_ENTITY)
+                .into_response(),
+        );
+    }
+
     let mut server = server_arc.lock().await;
 
     let lobby_id = Uuid::new_v4();
 
-    let new_lobby = models::Lobby {
-        round: 0,
-        tick: Uuid::new_v4(),
-        tick_length_milli_seconds: game::GAME_TICK_LENGTH,
-        client_messages: HashMap::new(),
-        id: lobby_id,
-        clients: HashMap::new(),
-        status: models::LobbyStatus::PENDING,
-        game_state: models::GameState {
-            players: HashMap::new(),
-            entities: Vec::new(),
-        },
-    };
+    let new_lobby = models::Lobby::new(lobby_id, GameEngine::new(config));
 
     server.lobbies.insert(lobby_id, new_lobby);
 
     let new_lobby_reply = api_models::LobbyCreateResponse { id: lobby_id };
 
-    Ok(warp::reply::json(&new_lobby_reply))
+    Ok(warp::reply::json(&new_lobby_reply).into_response())
 }
 
 async fn get_lobbies_list_reply(
@@ -148,8 +380,20 @@ async fn get_lobbies_list_reply(
             .values()
             .cloned()
             .map(|lobby| api_models::LobbyOut {
+                config: get_public_config(&lobby),
                 status: lobby.status,
                 id: lobby.id,
+                replay: lobby
+                    .playback
+                    .as_ref()
+                    .map(|playback| api_models::ReplayPlaybackOut {
+                        file_name: playback.file_name.clone(),
+                        round: replay::get_current_frame(playback).round,
+                        rounds: playback.frames.last().unwrap().round,
+                        paused: playback.paused,
+                        speed: playback.speed,
+                    }),
+                tournament_id: lobby.tournament_id,
                 clients: lobby.clients.values().cloned().collect(),
                 spectators: lobby
                     .clients
@@ -164,3 +408,256 @@ async fn get_lobbies_list_reply(
 
     Ok(warp::reply::json(&server_out))
 }
+
+/// Configuration of a lobby as reported to API clients. The seed is hidden until the lobby is
+/// finished, as bots could otherwise predict the random parts of the game.
+fn get_public_config(lobby: &models::Lobby) -> engine::GameConfig {
+    let mut config = lobby.engine.config().clone();
+
+    if lobby.status != models::LobbyStatus::FINISHED {
+        config.seed = None;
+    }
+
+    return config;
+}
+
+async fn get_replays_list_reply() -> Result<warp::reply::Response, Infallible> {
+    match replay::list_replays() {
+        Ok(file_names) => {
+            let replays_out = api_models::ReplaysOut {
+                replays: file_names
+                    .into_iter()
+                    .map(|file_name| api_models::ReplayOut { file_name })
+                    .collect(),
+            };
+
+            Ok(warp::reply::json(&replays_out).into_response())
+        }
+        Err(error_message) => Ok(warp::reply::with_status(
+            error_message,
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+        .into_response()),
+    }
+}
+
+async fn get_replay_verification_reply(
+    file_name: String,
+) -> Result<warp::reply::Response, Infallible> {
+    match replay::verify_replay(&file_name) {
+        Ok(verification) => Ok(warp::reply::json(&verification).into_response()),
+        Err(error_message) => Ok(warp::reply::with_status(
+            error_message,
+            StatusCode::UNPROCESSABLE_ENTITY,
+        )
+        .into_response()),
+    }
+}
+
+async fn get_create_replay_lobby_reply(
+    server_arc: models::ServerArc,
+    create_replay_lobby_body: api_models::CreateReplayLobbyBody,
+) -> Result<warp::reply::Response, Infallible> {
+    let (config, playback) = match replay::load_replay(&create_replay_lobby_body.file_name) {
+        Ok(replay) => replay,
+        Err(error_message) => {
+            return Ok(
+                warp::reply::with_status(error_message, StatusCode::UNPROCESSABLE_ENTITY)
+                    .into_response(),
+            );
+        }
+    };
+
+    let mut server = server_arc.lock().await;
+
+    let lobby_id = Uuid::new_v4();
+
+    let mut new_lobby = models::Lobby::new(lobby_id, GameEngine::new(config));
+    new_lobby.playback = Some(playback);
+
+    info!(
+        "Replay lobby with id '{}' created for replay '{}'",
+        lobby_id, create_replay_lobby_body.file_name
+    );
+
+    server.lobbies.insert(lobby_id, new_lobby);
+
+    let new_lobby_reply = api_models::LobbyCreateResponse { id: lobby_id };
+
+    Ok(warp::reply::json(&new_lobby_reply).into_response())
+}
+
+async fn get_update_replay_lobby_reply(
+    lobby_id: Uuid,
+    server_arc: models::ServerArc,
+    update_replay_lobby_body: api_models::UpdateReplayLobbyBody,
+) -> Result<impl warp::Reply, Infallible> {
+    let mut server = server_arc.lock().await;
+
+    let lobby = match server.lobbies.get_mut(&lobby_id) {
+        Some(lobby) => lobby,
+        None => {
+            return Ok(warp::reply::with_status(
+                format!("Lobby with id '{}' does not exist", lobby_id),
+                StatusCode::NOT_FOUND,
+            ))
+        }
+    };
+
+    let playback = match lobby.playback.as_mut() {
+        Some(playback) => playback,
+        None => {
+            return Ok(warp::reply::with_status(
+                format!("Lobby with id '{}' is not a replay lobby", lobby_id),
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ))
+        }
+    };
+
+    match replay::update_playback(playback, &update_replay_lobby_body) {
+        Ok(()) => {
+            info!(
+                "Playback of replay lobby with id '{}' was updated to {:?}",
+                lobby_id, update_replay_lobby_body
+            );
+            Ok(warp::reply::with_status("".to_string(), StatusCode::OK))
+        }
+        Err(error_message) => Ok(warp::reply::with_status(
+            error_message,
+            StatusCode::UNPROCESSABLE_ENTITY,
+        )),
+    }
+}
+
+async fn get_tournaments_list_reply(
+    server_arc: models::ServerArc,
+) -> Result<impl warp::Reply, Infallible> {
+    let server = server_arc.lock().await;
+
+    let tournaments_out = api_models::TournamentsOut {
+        tournaments: server.tournaments.values().cloned().collect(),
+    };
+
+    Ok(warp::reply::json(&tournaments_out))
+}
+
+async fn get_tournament_reply(
+    tournament_id: Uuid,
+    server_arc: models::ServerArc,
+) -> Result<warp::reply::Response, Infallible> {
+    let server = server_arc.lock().await;
+
+    match server.tournaments.get(&tournament_id) {
+        Some(tournament) => Ok(warp::reply::json(tournament).into_response()),
+        None => Ok(warp::reply::with_status(
+            format!("Tournament with id '{}' does not exist", tournament_id),
+            StatusCode::NOT_FOUND,
+        )
+        .into_response()),
+    }
+}
+
+async fn get_create_tournament_reply(
+    server_arc: models::ServerArc,
+    db_arc: models::DbArc,
+    map_registry: Arc<maps::MapRegistry>,
+    mut create_tournament_body: api_models::CreateTournamentBody,
+) -> Result<warp::reply::Response, Infallible> {
+    create_tournament_body.config =
+        match tournament::prepare_config(&map_registry, create_tournament_body.config) {
+            Ok(config) => config,
+            Err(error_message) => {
+                return Ok(warp::reply::with_status(
+                    error_message,
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                )
+                .into_response());
+            }
+        };
+
+    let mut server = server_arc.lock().await;
+
+    match tournament::create_tournament(&mut server, create_tournament_body) {
+        Ok(tournament_id) => {
+            tokio::spawn(tournament::run_tournament(
+                tournament_id,
+                server_arc.clone(),
+                db_arc.clone(),
+            ));
+
+            let tournament_reply = api_models::TournamentCreateResponse { id: tournament_id };
+
+            Ok(warp::reply::json(&tournament_reply).into_response())
+        }
+        Err(error_message) => Ok(warp::reply::with_status(
+            error_message,
+            StatusCode::UNPROCESSABLE_ENTITY,
+        )
+        .into_response()),
+    }
+}
+
+async fn get_leaderboard_reply(
+    server_arc: models::ServerArc,
+) -> Result<impl warp::Reply, Infallible> {
+    let server = server_arc.lock().await;
+
+    let leaderboard_out = api_models::LeaderboardOut {
+        players: server
+            .ratings
+            .leaderboard()
+            .into_iter()
+            .enumerate()
+            .map(|(index, player)| api_models::LeaderboardEntry {
+                rank: index + 1,
+                name: player.name.clone(),
+                rating: player.rating,
+                games: player.games,
+                wins: player.wins,
+            })
+            .collect(),
+    };
+
+    Ok(warp::reply::json(&leaderboard_out))
+}
+
+async fn get_player_history_reply(
+    name: String,
+    server_arc: models::ServerArc,
+) -> Result<warp::reply::Response, Infallible> {
+    // Path segments are not decoded by warp, but player names may contain e.g. spaces
+    let name = percent_encoding::percent_decode_str(&name)
+        .decode_utf8_lossy()
+        .to_string();
+
+    let server = server_arc.lock().await;
+
+    match server.ratings.player(&name) {
+        Some(player) => Ok(warp::reply::json(player).into_response()),
+        None => Ok(warp::reply::with_status(
+            format!("Player '{}' has not finished any rated game", name),
+            StatusCode::NOT_FOUND,
+        )
+        .into_response()),
+    }
+}
+
+async fn get_maps_list_reply(
+    map_registry: Arc<maps::MapRegistry>,
+) -> Result<impl warp::Reply, Infallible> {
+    let maps_out = api_models::MapsOut {
+        maps: map_registry
+            .maps()
+            .map(|map| api_models::MapOut {
+                name: map.name.clone(),
+                field_size_x: map.field_size_x,
+                field_size_y: map.field_size_y,
+                max_players: map.max_players,
+                obstacles: map.obstacles.len(),
+                pickups: map.pickups.len(),
+            })
+            .collect(),
+    };
+
+    Ok(warp::reply::json(&maps_out))
+}
@@ -0,0 +1,120 @@
+use engine::{GameConfig, GameMap, GENERATED_MAP_NAME};
+use log::info;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+const DEFAULT_MAPS_DIRECTORY: &str = "maps";
+
+/// Maps which can be chosen when creating a lobby. Besides the built-in maps `classic` and
+/// `generated`, every JSON file in the directory given by the `MAPS_DIRECTORY` environment variable
+/// is loaded at startup.
+pub struct MapRegistry {
+    maps: BTreeMap<String, GameMap>,
+}
+
+impl MapRegistry {
+    pub fn from_env() -> Result<MapRegistry, String> {
+        let directory = PathBuf::from(
+            env::var("MAPS_DIRECTORY").unwrap_or_else(|_| DEFAULT_MAPS_DIRECTORY.to_string()),
+        );
+
+        let classic = GameMap::classic();
+        let mut maps = BTreeMap::new();
+        maps.insert(classic.name.clone(), classic);
+
+        if !directory.exists() {
+            info!(
+                "No maps found at '{}', only the built-in map is available",
+                directory.display()
+            );
+
+            return Ok(MapRegistry { maps });
+        }
+
+        let entries = fs::read_dir(&directory).map_err(|err| {
+            format!(
+                "Could not read maps directory '{}'. Original error: {}.",
+                directory.display(),
+                err
+            )
+        })?;
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "json")
+            })
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let content = fs::read_to_string(&path).map_err(|err| {
+                format!(
+                    "Could not read map '{}'. Original error: {}.",
+                    path.display(),
+                    err
+                )
+            })?;
+
+            let map: GameMap = serde_json::from_str(&content).map_err(|err| {
+                format!(
+                    "Could not parse map '{}'. Original error: {}.",
+                    path.display(),
+                    err
+                )
+            })?;
+
+            map.validate()
+                .map_err(|error_message| format!("{} ({})", error_message, path.display()))?;
+
+            if maps.contains_key(&map.name) || map.name == GENERATED_MAP_NAME {
+                return Err(format!(
+                    "Map '{}' of '{}' reuses the name of another map",
+                    map.name,
+                    path.display()
+                ));
+            }
+
+            maps.insert(map.name.clone(), map);
+        }
+
+        info!(
+            "Loaded {} map(s) from '{}'",
+            maps.len() - 1,
+            directory.display()
+        );
+
+        return Ok(MapRegistry { maps });
+    }
+
+    /// All maps sorted by name.
+    pub fn maps(&self) -> impl Iterator<Item = &GameMap> {
+        return self.maps.values();
+    }
+
+    /// Applies the map named in the configuration. The map `generated` is generated from the map
+    /// seed of the configuration. Configurations without a map are left as they are.
+    pub fn apply_map(&self, config: &mut GameConfig) -> Result<(), String> {
+        let map_name = match &config.map {
+            Some(map_name) => map_name,
+            None => return Ok(()),
+        };
+
+        if map_name == GENERATED_MAP_NAME {
+            return GameMap::apply_generated_to(config);
+        }
+
+        let map = self
+            .maps
+            .get(map_name)
+            .ok_or_else(|| format!("Could not find map with name '{}'", map_name))?;
+
+        map.apply_to(config);
+
+        return Ok(());
+    }
+}
@@ -1,9 +1,12 @@
+use engine::protocol::GameOver;
+use engine::GameEngine;
 use futures_util::stream::SplitSink;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
 use tokio_tungstenite::WebSocketStream;
@@ -13,6 +16,9 @@ use tokio::net::TcpStream;
 use tokio_tungstenite::tungstenite::Message;
 
 use crate::api_models::ClientMessage;
+use crate::ratings::RatingStore;
+use crate::replay::{ReplayPlayback, ReplayRecorder};
+use crate::tournament::Tournament;
 
 #[derive(Debug, Serialize, Clone, PartialEq)]
 pub enum ClientType {
@@ -39,65 +45,67 @@ impl FromStr for ClientType {
     }
 }
 
-#[derive(Serialize, Clone)]
-pub enum EntityType {
-    PLAYER,
-}
-
-#[derive(Serialize, Clone)]
-pub struct Projectile {
-    pub id: Uuid,
-    pub previous_x: f64,
-    pub previous_y: f64,
-    pub x: f64,
-    pub y: f64,
-    pub travel_distance: f64,
-    pub direction: i32,
-    #[serde(skip)]
-    pub source: Uuid,
-}
-
-#[derive(Serialize, Clone)]
-pub struct Player {
-    pub entity_type: EntityType,
-    pub id: Uuid,
-    pub name: String,
-    pub x: i32,
-    pub y: i32,
-    pub rotation: i32,
-    pub color: String,
-    pub damage_inflicted_by: Vec<Uuid>,
-    pub health: i16,
-    pub last_action_success: bool,
-    pub error_message: String,
-}
-
-#[derive(Serialize, Clone)]
-pub struct GameState {
-    pub players: HashMap<SocketAddr, Player>,
-    pub entities: Vec<Projectile>,
-}
-
 #[derive(Debug, Serialize, Clone)]
 pub struct Client {
     pub client_type: ClientType,
     pub username: String,
+    pub protocol_version: u32,
+}
+
+/// Connection of a player to a game, which survives the loss of the websocket connection. A
+/// disconnected player keeps their place in a running game until the grace period ran out.
+#[derive(Clone)]
+pub struct PlayerSession {
+    pub player_id: Uuid,
+    pub username: String,
+    pub addr: Option<SocketAddr>,
+    pub disconnected_at: Option<Instant>,
 }
 
 #[derive(Clone)]
 pub struct Lobby {
-    pub round: i32,
-    pub tick: Uuid,
-    pub tick_length_milli_seconds: u64,
     pub id: Uuid,
     pub clients: HashMap<SocketAddr, Client>,
+    pub players: HashMap<SocketAddr, Uuid>,
+    pub sessions: HashMap<String, PlayerSession>,
     pub client_messages: HashMap<SocketAddr, ClientMessage>,
+    pub late_client_messages: Vec<(SocketAddr, ClientMessage)>,
     pub status: LobbyStatus,
-    pub game_state: GameState,
+    pub engine: GameEngine,
+    pub replay: Option<ReplayRecorder>,
+    pub playback: Option<ReplayPlayback>,
+    pub tournament_id: Option<Uuid>,
+    /// Names of the only players allowed to join the lobby, e.g. the participants of a tournament match.
+    pub reserved_for: Option<Vec<String>>,
+    /// Result of the last game, stored when the lobby is finished. Later changes of the engine,
+    /// e.g. by disconnecting players, do not alter it.
+    pub result: Option<GameOver>,
+}
+
+impl Lobby {
+    pub fn new(id: Uuid, engine: GameEngine) -> Lobby {
+        return Lobby {
+            id,
+            clients: HashMap::new(),
+            players: HashMap::new(),
+            sessions: HashMap::new(),
+            client_messages: HashMap::new(),
+            late_client_messages: Vec::new(),
+            status: LobbyStatus::PENDING,
+            engine,
+            replay: None,
+            playback: None,
+            tournament_id: None,
+            reserved_for: None,
+            result: None,
+        };
+    }
 }
 
 pub struct Server {
     pub lobbies: HashMap<Uuid, Lobby>,
+    pub tournaments: HashMap<Uuid, Tournament>,
+    pub ratings: RatingStore,
 }
 
 pub type ServerArc = Arc<Mutex<Server>>;
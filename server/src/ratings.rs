@@ -0,0 +1,258 @@
+use engine::Standing;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+use crate::models;
+
+const DEFAULT_RATINGS_PATH: &str = "ratings.json";
+const INITIAL_RATING: f64 = 1500.0;
+const K_FACTOR: f64 = 32.0;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RatedGame {
+    pub lobby_id: Uuid,
+    pub finished_at_milli_seconds: u64,
+    pub placement: usize,
+    pub player_count: usize,
+    pub opponents: Vec<String>,
+    pub rating_before: f64,
+    pub rating_after: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PlayerRating {
+    pub name: String,
+    pub rating: f64,
+    pub games: i32,
+    pub wins: i32,
+    pub history: Vec<RatedGame>,
+}
+
+/// Elo ratings of all players, which finished at least one game. The ratings are stored as JSON
+/// file, its path is read from the `RATINGS_PATH` environment variable.
+///
+/// Games are only rated if the team registry is enabled, as players can otherwise join with any
+/// username and take over the rating of another player.
+pub struct RatingStore {
+    path: PathBuf,
+    players: HashMap<String, PlayerRating>,
+    rates_games: bool,
+}
+
+impl RatingStore {
+    pub fn from_env(rates_games: bool) -> Result<RatingStore, String> {
+        let path = PathBuf::from(
+            env::var("RATINGS_PATH").unwrap_or_else(|_| DEFAULT_RATINGS_PATH.to_string()),
+        );
+
+        if !path.exists() {
+            info!(
+                "No ratings found at '{}', starting with empty leaderboard",
+                path.display()
+            );
+
+            return Ok(RatingStore {
+                path,
+                players: HashMap::new(),
+                rates_games,
+            });
+        }
+
+        let content = fs::read_to_string(&path).map_err(|err| {
+            format!(
+                "Could not read ratings '{}'. Original error: {}.",
+                path.display(),
+                err
+            )
+        })?;
+
+        let players: Vec<PlayerRating> = serde_json::from_str(&content).map_err(|err| {
+            format!(
+                "Could not parse ratings '{}'. Original error: {}.",
+                path.display(),
+                err
+            )
+        })?;
+
+        info!(
+            "Loaded ratings of {} player(s) from '{}'",
+            players.len(),
+            path.display()
+        );
+
+        return Ok(RatingStore {
+            path,
+            players: players
+                .into_iter()
+                .map(|player| (player.name.clone(), player))
+                .collect(),
+            rates_games,
+        });
+    }
+
+    pub fn player(&self, name: &str) -> Option<&PlayerRating> {
+        return self.players.get(name);
+    }
+
+    /// All players, the highest rating first.
+    pub fn leaderboard(&self) -> Vec<&PlayerRating> {
+        let mut players: Vec<&PlayerRating> = self.players.values().collect();
+
+        players.sort_by(|left, right| {
+            right
+                .rating
+                .total_cmp(&left.rating)
+                .then_with(|| left.name.cmp(&right.name))
+        });
+
+        return players;
+    }
+
+    /// Updates the ratings with the final standings of a game. Every player is compared with
+    /// every other player of the game, a better placement counts as win and the same placement
+    /// as draw against that opponent.
+    pub fn record_game(&mut self, lobby_id: Uuid, standings: &[Standing]) {
+        if !self.rates_games || standings.len() < 2 {
+            return;
+        }
+
+        let mut names = HashSet::new();
+
+        if !standings
+            .iter()
+            .all(|standing| names.insert(&standing.name))
+        {
+            info!(
+                "Not rating game of lobby with id '{}', because several players share a name",
+                lobby_id
+            );
+            return;
+        }
+
+        let finished_at_milli_seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+
+        let ratings_before: Vec<f64> = standings
+            .iter()
+            .map(|standing| {
+                self.players
+                    .get(&standing.name)
+                    .map(|player| player.rating)
+                    .unwrap_or(INITIAL_RATING)
+            })
+            .collect();
+
+        // The K factor is split between the opponents, so a game with many players does not move
+        // the ratings more than a duel
+        let k_factor = K_FACTOR / (standings.len() - 1) as f64;
+
+        for (index, standing) in standings.iter().enumerate() {
+            let mut rating_change = 0.0;
+
+            for (other_index, other) in standings.iter().enumerate() {
+                if index == other_index {
+                    continue;
+                }
+
+                let score = match standing.placement.cmp(&other.placement) {
+                    std::cmp::Ordering::Less => 1.0,
+                    std::cmp::Ordering::Equal => 0.5,
+                    std::cmp::Ordering::Greater => 0.0,
+                };
+
+                let expected_score = 1.0
+                    / (1.0
+                        + 10f64
+                            .powf((ratings_before[other_index] - ratings_before[index]) / 400.0));
+
+                rating_change += k_factor * (score - expected_score);
+            }
+
+            let rating_after = ratings_before[index] + rating_change;
+            let is_sole_winner = standing.placement == 1
+                && standings
+                    .iter()
+                    .filter(|other| other.placement == 1)
+                    .count()
+                    == 1;
+
+            let player = self
+                .players
+                .entry(standing.name.clone())
+                .or_insert_with(|| PlayerRating {
+                    name: standing.name.clone(),
+                    rating: INITIAL_RATING,
+                    games: 0,
+                    wins: 0,
+                    history: vec![],
+                });
+
+            player.rating = rating_after;
+            player.games += 1;
+
+            if is_sole_winner {
+                player.wins += 1;
+            }
+
+            player.history.push(RatedGame {
+                lobby_id,
+                finished_at_milli_seconds,
+                placement: standing.placement,
+                player_count: standings.len(),
+                opponents: standings
+                    .iter()
+                    .filter(|other| other.player_id != standing.player_id)
+                    .map(|other| other.name.clone())
+                    .collect(),
+                rating_before: ratings_before[index],
+                rating_after,
+            });
+
+            info!(
+                "Rating of player '{}' changed from {:.1} to {:.1}",
+                standing.name, ratings_before[index], rating_after
+            );
+        }
+
+        self.save();
+    }
+
+    fn save(&self) {
+        let mut players: Vec<&PlayerRating> = self.players.values().collect();
+        players.sort_by(|left, right| left.name.cmp(&right.name));
+
+        // Write to a temporary file first, so a crash does not leave behind truncated ratings
+        let temporary_path = self.path.with_extension("json.tmp");
+
+        let result = fs::write(&temporary_path, serde_json::to_string(&players).unwrap())
+            .and_then(|_| fs::rename(&temporary_path, &self.path));
+
+        if let Err(err) = result {
+            error!(
+                "Could not save ratings to '{}'. Original error: {}.",
+                self.path.display(),
+                err
+            );
+        }
+    }
+}
+
+/// Rates a game, which was finished by the engine. Games of lobbies stopped via the management
+/// API are not rated.
+pub async fn record_finished_game(
+    lobby_id: Uuid,
+    standings: Vec<Standing>,
+    server_arc: models::ServerArc,
+) {
+    let mut server = server_arc.lock().await;
+
+    server.ratings.record_game(lobby_id, &standings);
+}
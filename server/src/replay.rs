@@ -0,0 +1,559 @@
+use engine::{GameConfig, GameEngine, Player, PlayerAction};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time;
+use uuid::Uuid;
+
+use crate::api_models;
+use crate::game;
+use crate::models;
+
+const DEFAULT_REPLAY_DIRECTORY: &str = "replays";
+const MAX_PLAYBACK_SPEED: f64 = 16.0;
+
+#[derive(Clone)]
+pub struct ReplayRecorder {
+    pub path: PathBuf,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub enum ReplayActionStatus {
+    RECEIVED,
+    MISSING,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReplayAction {
+    pub player_id: Uuid,
+    pub status: ReplayActionStatus,
+    pub message: Option<api_models::ClientMessage>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReplayLateMessage {
+    pub player_id: Uuid,
+    pub message: api_models::ClientMessage,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ReplayRecord {
+    HEADER {
+        lobby_id: Uuid,
+        started_at_milli_seconds: u64,
+        config: GameConfig,
+        players: Vec<Player>,
+    },
+    TICK {
+        round: i32,
+        actions: Vec<ReplayAction>,
+        late_messages: Vec<ReplayLateMessage>,
+        game_state: api_models::GameStateOut,
+    },
+    DISCONNECT {
+        round: i32,
+        player_id: Uuid,
+    },
+    END {
+        round: i32,
+        status: models::LobbyStatus,
+    },
+}
+
+#[derive(Clone)]
+pub struct ReplayFrame {
+    pub round: i32,
+    pub game_state: api_models::GameStateOut,
+}
+
+#[derive(Clone)]
+pub struct ReplayPlayback {
+    pub run_id: Uuid,
+    pub file_name: String,
+    pub frames: Arc<Vec<ReplayFrame>>,
+    pub position: usize,
+    pub paused: bool,
+    pub speed: f64,
+}
+
+pub struct TickInput {
+    pub actions: Vec<ReplayAction>,
+    pub late_messages: Vec<ReplayLateMessage>,
+}
+
+pub fn get_replay_directory() -> PathBuf {
+    return PathBuf::from(
+        env::var("REPLAY_DIRECTORY").unwrap_or_else(|_| DEFAULT_REPLAY_DIRECTORY.to_string()),
+    );
+}
+
+pub fn start_recording(lobby: &mut models::Lobby) {
+    stop_recording(lobby);
+
+    let replay_directory = get_replay_directory();
+
+    if let Err(err) = fs::create_dir_all(&replay_directory) {
+        error!(
+            "Could not create replay directory '{}'. Original error: {}.",
+            replay_directory.display(),
+            err
+        );
+        return;
+    }
+
+    let started_at_milli_seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+
+    let path = replay_directory.join(format!("{}_{}.jsonl", lobby.id, started_at_milli_seconds));
+
+    info!(
+        "Recording replay of lobby with id '{}' to '{}'",
+        lobby.id,
+        path.display()
+    );
+
+    lobby.replay = Some(ReplayRecorder { path });
+
+    let header = ReplayRecord::HEADER {
+        lobby_id: lobby.id,
+        started_at_milli_seconds,
+        config: lobby.engine.config().clone(),
+        players: lobby.engine.players().cloned().collect(),
+    };
+
+    write_record(lobby, &header);
+}
+
+pub fn collect_tick_input(lobby: &mut models::Lobby) -> TickInput {
+    let player_addresses: HashMap<Uuid, SocketAddr> = lobby
+        .players
+        .iter()
+        .map(|(addr, player_id)| (*player_id, *addr))
+        .collect();
+
+    let actions = lobby
+        .engine
+        .players()
+        .map(|player| {
+            match player_addresses
+                .get(&player.id)
+                .and_then(|addr| lobby.client_messages.get(addr))
+            {
+                Some(client_message) => ReplayAction {
+                    player_id: player.id,
+                    status: ReplayActionStatus::RECEIVED,
+                    message: Some(client_message.clone()),
+                },
+                None => ReplayAction {
+                    player_id: player.id,
+                    status: ReplayActionStatus::MISSING,
+                    message: None,
+                },
+            }
+        })
+        .collect();
+
+    let late_messages = std::mem::take(&mut lobby.late_client_messages)
+        .into_iter()
+        .filter_map(|(addr, message)| {
+            lobby.players.get(&addr).map(|player_id| ReplayLateMessage {
+                player_id: *player_id,
+                message,
+            })
+        })
+        .collect();
+
+    return TickInput {
+        actions,
+        late_messages,
+    };
+}
+
+pub fn record_tick(
+    lobby: &mut models::Lobby,
+    tick_input: TickInput,
+    game_state_out: api_models::GameStateOut,
+) {
+    let record = ReplayRecord::TICK {
+        round: lobby.engine.round(),
+        actions: tick_input.actions,
+        late_messages: tick_input.late_messages,
+        game_state: game_state_out,
+    };
+
+    write_record(lobby, &record);
+}
+
+pub fn record_disconnect(lobby: &mut models::Lobby, player_id: Uuid) {
+    let record = ReplayRecord::DISCONNECT {
+        round: lobby.engine.round(),
+        player_id,
+    };
+
+    write_record(lobby, &record);
+}
+
+pub fn stop_recording(lobby: &mut models::Lobby) {
+    if lobby.replay.is_none() {
+        return;
+    }
+
+    let record = ReplayRecord::END {
+        round: lobby.engine.round(),
+        status: lobby.status.clone(),
+    };
+
+    write_record(lobby, &record);
+
+    info!("Stopped recording replay of lobby with id '{}'", lobby.id);
+
+    lobby.replay = None;
+}
+
+fn write_record(lobby: &mut models::Lobby, record: &ReplayRecord) {
+    let recorder = match &lobby.replay {
+        Some(recorder) => recorder,
+        None => return,
+    };
+
+    let mut line = serde_json::to_string(record).unwrap();
+    line.push('\n');
+
+    let result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&recorder.path)
+        .and_then(|mut file| file.write_all(line.as_bytes()));
+
+    if let Err(err) = result {
+        error!(
+            "Failed to write replay of lobby with id '{}' to '{}', recording is stopped. Original error: {}.",
+            lobby.id,
+            recorder.path.display(),
+            err
+        );
+        lobby.replay = None;
+    }
+}
+
+pub fn list_replays() -> Result<Vec<String>, String> {
+    let replay_directory = get_replay_directory();
+
+    if !replay_directory.exists() {
+        return Ok(vec![]);
+    }
+
+    let entries = fs::read_dir(&replay_directory).map_err(|err| {
+        format!(
+            "Could not read replay directory '{}'. Original error: {}.",
+            replay_directory.display(),
+            err
+        )
+    })?;
+
+    let mut file_names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|file_name| file_name.ends_with(".jsonl"))
+        .collect();
+
+    file_names.sort();
+
+    return Ok(file_names);
+}
+
+fn read_replay_records(file_name: &str) -> Result<Vec<ReplayRecord>, String> {
+    if file_name.is_empty()
+        || file_name.contains('/')
+        || file_name.contains('\\')
+        || file_name.contains("..")
+    {
+        return Err(format!("'{}' is not a valid replay file name", file_name));
+    }
+
+    let path = get_replay_directory().join(file_name);
+
+    let content = fs::read_to_string(&path).map_err(|err| {
+        format!(
+            "Could not read replay '{}'. Original error: {}.",
+            file_name, err
+        )
+    })?;
+
+    let mut records = vec![];
+
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record: ReplayRecord = serde_json::from_str(line).map_err(|err| {
+            format!(
+                "Could not parse line {} of replay '{}'. Original error: {}.",
+                index + 1,
+                file_name,
+                err
+            )
+        })?;
+
+        records.push(record);
+    }
+
+    match records.first() {
+        Some(ReplayRecord::HEADER { .. }) => Ok(records),
+        _ => Err(format!("Replay '{}' has no header", file_name)),
+    }
+}
+
+pub fn load_replay(file_name: &str) -> Result<(GameConfig, ReplayPlayback), String> {
+    let mut config = None;
+    let mut frames = vec![];
+
+    for record in read_replay_records(file_name)? {
+        match record {
+            ReplayRecord::HEADER {
+                config: header_config,
+                ..
+            } => config = Some(header_config),
+            ReplayRecord::TICK {
+                round, game_state, ..
+            } => frames.push(ReplayFrame { round, game_state }),
+            ReplayRecord::DISCONNECT { .. } | ReplayRecord::END { .. } => {}
+        }
+    }
+
+    if frames.is_empty() {
+        return Err(format!("Replay '{}' contains no game ticks", file_name));
+    }
+
+    let playback = ReplayPlayback {
+        run_id: Uuid::nil(),
+        file_name: file_name.to_string(),
+        frames: Arc::new(frames),
+        position: 0,
+        paused: false,
+        speed: 1.0,
+    };
+
+    return Ok((config.unwrap(), playback));
+}
+
+/// Simulates the game again from the roster and the actions stored in a replay and compares the
+/// resulting game states with the recorded ones.
+pub fn verify_replay(file_name: &str) -> Result<api_models::ReplayVerificationOut, String> {
+    let mut records = read_replay_records(file_name)?.into_iter();
+
+    let (config, players) = match records.next() {
+        Some(ReplayRecord::HEADER {
+            config, players, ..
+        }) => (config, players),
+        _ => return Err(format!("Replay '{}' has no header", file_name)),
+    };
+
+    if config.seed.is_none() {
+        return Err(format!(
+            "Replay '{}' was recorded without a seed and cannot be verified",
+            file_name
+        ));
+    }
+
+    let mut engine = GameEngine::with_players(config, players);
+    engine.start();
+
+    let mut verified_rounds = 0;
+
+    for record in records {
+        match record {
+            ReplayRecord::TICK {
+                round,
+                actions,
+                game_state,
+                ..
+            } => {
+                let player_actions: HashMap<Uuid, PlayerAction> = actions
+                    .into_iter()
+                    .filter_map(|action| {
+                        action
+                            .message
+                            .map(|message| (action.player_id, message.player_action))
+                    })
+                    .collect();
+
+                engine.apply_actions(&player_actions);
+
+                let simulated_game_state = engine.game_state_out(game_state.spectators);
+
+                if engine.round() != round
+                    || serde_json::to_string(&simulated_game_state).unwrap()
+                        != serde_json::to_string(&game_state).unwrap()
+                {
+                    return Ok(api_models::ReplayVerificationOut {
+                        file_name: file_name.to_string(),
+                        deterministic: false,
+                        verified_rounds,
+                        mismatch_round: Some(round),
+                    });
+                }
+
+                verified_rounds = round;
+            }
+            ReplayRecord::DISCONNECT { player_id, .. } => {
+                engine.eliminate_player(&player_id);
+                engine.advance_tick();
+            }
+            ReplayRecord::HEADER { .. } | ReplayRecord::END { .. } => break,
+        }
+    }
+
+    return Ok(api_models::ReplayVerificationOut {
+        file_name: file_name.to_string(),
+        deterministic: true,
+        verified_rounds,
+        mismatch_round: None,
+    });
+}
+
+pub fn get_current_frame(playback: &ReplayPlayback) -> &ReplayFrame {
+    let index = std::cmp::min(playback.position, playback.frames.len() - 1);
+    return &playback.frames[index];
+}
+
+pub fn update_playback(
+    playback: &mut ReplayPlayback,
+    update: &api_models::UpdateReplayLobbyBody,
+) -> Result<(), String> {
+    if let Some(speed) = update.speed {
+        if !speed.is_finite() || speed <= 0.0 || speed > MAX_PLAYBACK_SPEED {
+            return Err(format!(
+                "'speed' must be greater than 0 and at most {}",
+                MAX_PLAYBACK_SPEED
+            ));
+        }
+    }
+
+    let seek_position = match update.round {
+        Some(round) => match playback
+            .frames
+            .iter()
+            .position(|frame| frame.round >= round)
+        {
+            Some(position) => Some(position),
+            None => {
+                return Err(format!(
+                    "Cannot seek to round {}, because the replay ends at round {}",
+                    round,
+                    playback.frames.last().unwrap().round
+                ))
+            }
+        },
+        None => None,
+    };
+
+    if let Some(speed) = update.speed {
+        playback.speed = speed;
+    }
+
+    if let Some(paused) = update.paused {
+        playback.paused = paused;
+    }
+
+    if let Some(position) = seek_position {
+        playback.position = position;
+    }
+
+    return Ok(());
+}
+
+pub async fn start_playback(lobby_id: Uuid, server_arc: models::ServerArc, db_arc: models::DbArc) {
+    let mut server = server_arc.lock().await;
+
+    let lobby = server.lobbies.get_mut(&lobby_id).unwrap();
+
+    let playback = lobby.playback.as_mut().unwrap();
+
+    playback.run_id = Uuid::new_v4();
+
+    if playback.position >= playback.frames.len() {
+        playback.position = 0;
+    }
+
+    info!(
+        "Starting playback of replay '{}' in lobby with id '{}'",
+        playback.file_name, lobby_id
+    );
+
+    tokio::spawn(run_playback(
+        playback.run_id,
+        lobby_id,
+        server_arc.clone(),
+        db_arc.clone(),
+    ));
+}
+
+async fn run_playback(
+    run_id: Uuid,
+    lobby_id: Uuid,
+    server_arc: models::ServerArc,
+    db_arc: models::DbArc,
+) {
+    loop {
+        let delay = {
+            let mut server = server_arc.lock().await;
+
+            let lobby = match server.lobbies.get_mut(&lobby_id) {
+                Some(lobby) => lobby,
+                None => return,
+            };
+
+            if lobby.playback.as_ref().unwrap().run_id != run_id
+                || lobby.status != models::LobbyStatus::RUNNING
+            {
+                info!(
+                    "Stopping playback in lobby with id '{}', because lobby is no longer running",
+                    lobby_id
+                );
+                return;
+            }
+
+            let playback = lobby.playback.as_ref().unwrap();
+            let paused = playback.paused;
+            let speed = playback.speed;
+            let tick_length_milli_seconds = get_current_frame(playback)
+                .game_state
+                .tick_length_milli_seconds;
+
+            if !paused {
+                game::push_game_state_to_everyone(lobby, db_arc.clone());
+
+                let playback = lobby.playback.as_mut().unwrap();
+                playback.position += 1;
+
+                if playback.position >= playback.frames.len() {
+                    info!(
+                        "Playback of replay '{}' reached its end, stopping lobby.",
+                        playback.file_name
+                    );
+                    lobby.status = models::LobbyStatus::FINISHED;
+                    return;
+                }
+            }
+
+            Duration::from_millis(std::cmp::max(
+                1,
+                (tick_length_milli_seconds as f64 / speed) as u64,
+            ))
+        };
+
+        time::sleep(delay).await;
+    }
+}
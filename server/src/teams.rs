@@ -0,0 +1,80 @@
+use log::{info, warn};
+use serde::Deserialize;
+use std::env;
+use std::fs;
+
+use crate::auth;
+
+#[derive(Deserialize, Clone)]
+pub struct Team {
+    pub name: String,
+    pub token: String,
+}
+
+/// Teams allowed to join lobbies as players. The registry is a JSON file with a list of teams and
+/// their pre-issued tokens, its path is read from the `TEAM_REGISTRY_PATH` environment variable.
+pub struct TeamRegistry {
+    teams: Option<Vec<Team>>,
+}
+
+impl TeamRegistry {
+    pub fn from_env() -> Result<TeamRegistry, String> {
+        let path = match env::var("TEAM_REGISTRY_PATH") {
+            Ok(path) => path,
+            Err(_) => {
+                warn!("No team registry configured, players can join with any username and games are not rated. Set TEAM_REGISTRY_PATH to require team tokens.");
+                return Ok(TeamRegistry { teams: None });
+            }
+        };
+
+        let content = fs::read_to_string(&path).map_err(|err| {
+            format!(
+                "Could not read team registry '{}'. Original error: {}.",
+                path, err
+            )
+        })?;
+
+        let teams: Vec<Team> = serde_json::from_str(&content).map_err(|err| {
+            format!(
+                "Could not parse team registry '{}'. Original error: {}.",
+                path, err
+            )
+        })?;
+
+        for (index, team) in teams.iter().enumerate() {
+            if team.name.is_empty() || team.token.is_empty() {
+                return Err(format!(
+                    "Team {} of team registry '{}' has an empty name or token",
+                    index + 1,
+                    path
+                ));
+            }
+
+            if teams[..index]
+                .iter()
+                .any(|other| other.name == team.name || other.token == team.token)
+            {
+                return Err(format!(
+                    "Team '{}' of team registry '{}' reuses the name or token of another team",
+                    team.name, path
+                ));
+            }
+        }
+
+        info!("Loaded {} team(s) from registry '{}'", teams.len(), path);
+
+        return Ok(TeamRegistry { teams: Some(teams) });
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        return self.teams.is_some();
+    }
+
+    pub fn find_team(&self, token: &str) -> Option<&Team> {
+        return self
+            .teams
+            .as_ref()?
+            .iter()
+            .find(|team| auth::constant_time_equals(team.token.as_bytes(), token.as_bytes()));
+    }
+}
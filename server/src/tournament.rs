@@ -0,0 +1,559 @@
+use engine::{GameConfig, GameEngine};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tokio::time;
+use uuid::Uuid;
+
+use crate::api_models;
+use crate::game;
+use crate::maps;
+use crate::models;
+
+const POLL_INTERVAL_MILLI_SECONDS: u64 = 1000;
+const DEFAULT_CONNECT_TIMEOUT_SECONDS: u64 = 120;
+const PLAYERS_PER_MATCH: usize = 2;
+const MAX_GAMES_PER_ELIMINATION_MATCH: i32 = 3;
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum TournamentFormat {
+    SINGLE_ELIMINATION,
+    DOUBLE_ELIMINATION,
+    ROUND_ROBIN,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub enum TournamentStatus {
+    RUNNING,
+    FINISHED,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub enum MatchStatus {
+    WAITING_FOR_PLAYERS,
+    RUNNING,
+    FINISHED,
+}
+
+#[derive(Serialize, Clone)]
+pub struct TournamentParticipant {
+    pub name: String,
+    pub seed: usize,
+    pub wins: i32,
+    pub losses: i32,
+    pub draws: i32,
+    pub points: i32,
+    pub byes: i32,
+    pub eliminated: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct TournamentMatch {
+    pub id: Uuid,
+    pub round: i32,
+    pub participants: Vec<String>,
+    pub lobby_id: Uuid,
+    /// Number of games played for this match. Drawn elimination matches are played again.
+    pub games: i32,
+    pub status: MatchStatus,
+    pub winner: Option<String>,
+    #[serde(skip)]
+    waiting_since: Instant,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Tournament {
+    pub id: Uuid,
+    pub name: String,
+    pub format: TournamentFormat,
+    pub status: TournamentStatus,
+    pub round: i32,
+    pub config: GameConfig,
+    pub connect_timeout_seconds: u64,
+    pub participants: Vec<TournamentParticipant>,
+    pub matches: Vec<TournamentMatch>,
+    pub winner: Option<String>,
+}
+
+/// Applies the map of the configuration of a tournament and validates it. Generating a map may
+/// take a while, so this is done before the server is locked.
+pub fn prepare_config(
+    map_registry: &maps::MapRegistry,
+    mut config: GameConfig,
+) -> Result<GameConfig, String> {
+    config.max_players = PLAYERS_PER_MATCH;
+    config.validate_limits()?;
+    map_registry.apply_map(&mut config)?;
+    config.max_players = PLAYERS_PER_MATCH;
+    config.validate()?;
+
+    return Ok(config);
+}
+
+/// Creates a tournament, the configuration of the body has to be prepared with `prepare_config`.
+pub fn create_tournament(
+    server: &mut models::Server,
+    body: api_models::CreateTournamentBody,
+) -> Result<Uuid, String> {
+    if body.participants.len() < 2 {
+        return Err("A tournament needs at least 2 participants".to_string());
+    }
+
+    let mut names = HashSet::new();
+
+    for name in &body.participants {
+        if name.is_empty() {
+            return Err("Participant names must not be empty".to_string());
+        }
+
+        if !names.insert(name) {
+            return Err(format!("Participant '{}' is listed more than once", name));
+        }
+    }
+
+    let tournament_id = Uuid::new_v4();
+
+    let mut tournament = Tournament {
+        id: tournament_id,
+        name: body
+            .name
+            .unwrap_or_else(|| format!("Tournament {}", tournament_id)),
+        format: body.format,
+        status: TournamentStatus::RUNNING,
+        round: 0,
+        config: body.config,
+        connect_timeout_seconds: body
+            .connect_timeout_seconds
+            .unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECONDS),
+        participants: body
+            .participants
+            .into_iter()
+            .enumerate()
+            .map(|(index, name)| TournamentParticipant {
+                name,
+                seed: index + 1,
+                wins: 0,
+                losses: 0,
+                draws: 0,
+                points: 0,
+                byes: 0,
+                eliminated: false,
+            })
+            .collect(),
+        matches: vec![],
+        winner: None,
+    };
+
+    start_next_round(&mut tournament, &mut server.lobbies);
+
+    info!(
+        "Created {:?} tournament '{}' with {} participants",
+        tournament.format,
+        tournament.name,
+        tournament.participants.len()
+    );
+
+    server.tournaments.insert(tournament_id, tournament);
+
+    return Ok(tournament_id);
+}
+
+/// Drives a tournament until it is finished: starts lobbies once all assigned bots are connected,
+/// collects the results of finished lobbies and creates the lobbies of the next round.
+pub async fn run_tournament(
+    tournament_id: Uuid,
+    server_arc: models::ServerArc,
+    db_arc: models::DbArc,
+) {
+    loop {
+        time::sleep(Duration::from_millis(POLL_INTERVAL_MILLI_SECONDS)).await;
+
+        let mut server_guard = server_arc.lock().await;
+        let server = &mut *server_guard;
+
+        let tournament = match server.tournaments.get_mut(&tournament_id) {
+            Some(tournament) => tournament,
+            None => return,
+        };
+
+        let lobbies_to_start = update_tournament(tournament, &mut server.lobbies);
+
+        for lobby_id in lobbies_to_start {
+            tokio::spawn(game::start_game_for_lobby(
+                lobby_id,
+                server_arc.clone(),
+                db_arc.clone(),
+            ));
+        }
+
+        if tournament.status == TournamentStatus::FINISHED {
+            info!(
+                "Tournament '{}' finished, winner: {:?}",
+                tournament.name, tournament.winner
+            );
+            return;
+        }
+    }
+}
+
+/// Checks the lobbies of all open matches and returns the lobbies, which have to be started.
+fn update_tournament(
+    tournament: &mut Tournament,
+    lobbies: &mut HashMap<Uuid, models::Lobby>,
+) -> Vec<Uuid> {
+    let mut lobbies_to_start = vec![];
+    let mut match_results = vec![];
+    let connect_timeout = Duration::from_secs(tournament.connect_timeout_seconds);
+
+    for tournament_match in tournament.matches.iter_mut() {
+        if tournament_match.status == MatchStatus::FINISHED {
+            continue;
+        }
+
+        let lobby = match lobbies.get_mut(&tournament_match.lobby_id) {
+            Some(lobby) => lobby,
+            None => continue,
+        };
+
+        match lobby.status {
+            models::LobbyStatus::PENDING => {
+                let connected: Vec<String> = tournament_match
+                    .participants
+                    .iter()
+                    .filter(|name| {
+                        lobby.clients.values().any(|client| {
+                            client.client_type == models::ClientType::PLAYER
+                                && client.username == **name
+                        })
+                    })
+                    .cloned()
+                    .collect();
+
+                if connected.len() == tournament_match.participants.len() {
+                    info!(
+                        "All participants of match {:?} connected, starting lobby with id '{}'",
+                        tournament_match.participants, lobby.id
+                    );
+                    lobby.status = models::LobbyStatus::RUNNING;
+                    tournament_match.status = MatchStatus::RUNNING;
+                    lobbies_to_start.push(lobby.id);
+                } else if tournament_match.waiting_since.elapsed() >= connect_timeout {
+                    // Participants which connected in time win by walkover. If nobody connected,
+                    // the better seed advances in elimination brackets.
+                    let winner = match connected.len() {
+                        1 => connected.first().cloned(),
+                        0 if tournament.format != TournamentFormat::ROUND_ROBIN => {
+                            tournament_match.participants.first().cloned()
+                        }
+                        _ => None,
+                    };
+
+                    info!(
+                        "Participants of match {:?} did not connect in time, walkover winner: {:?}",
+                        tournament_match.participants, winner
+                    );
+
+                    lobby.status = models::LobbyStatus::FINISHED;
+                    tournament_match.status = MatchStatus::FINISHED;
+                    tournament_match.winner = winner.clone();
+                    match_results.push((tournament_match.participants.clone(), winner, true));
+                }
+            }
+            models::LobbyStatus::RUNNING => {
+                tournament_match.status = MatchStatus::RUNNING;
+            }
+            models::LobbyStatus::FINISHED => {
+                // The stored result is used, as players may have left the engine since
+                let mut winner = lobby
+                    .result
+                    .as_ref()
+                    .and_then(|result| {
+                        let winner_id = result.winner_id?;
+                        return result
+                            .standings
+                            .iter()
+                            .find(|standing| standing.player_id == winner_id);
+                    })
+                    .map(|standing| standing.name.clone())
+                    .filter(|name| tournament_match.participants.contains(name));
+
+                let is_elimination = tournament.format != TournamentFormat::ROUND_ROBIN;
+
+                if winner.is_none()
+                    && is_elimination
+                    && tournament_match.games >= MAX_GAMES_PER_ELIMINATION_MATCH
+                {
+                    winner = tournament_match.participants.first().cloned();
+                    info!(
+                        "Match {:?} was drawn {} times, better seed {:?} advances",
+                        tournament_match.participants, tournament_match.games, winner
+                    );
+                }
+
+                if winner.is_none() && is_elimination {
+                    info!(
+                        "Match {:?} ended in a draw, playing it again",
+                        tournament_match.participants
+                    );
+
+                    let lobby_id = create_match_lobby(
+                        &tournament.config,
+                        tournament.id,
+                        &tournament_match.participants,
+                        lobbies,
+                    );
+
+                    tournament_match.lobby_id = lobby_id;
+                    tournament_match.games += 1;
+                    tournament_match.status = MatchStatus::WAITING_FOR_PLAYERS;
+                    tournament_match.waiting_since = Instant::now();
+                    continue;
+                }
+
+                tournament_match.status = MatchStatus::FINISHED;
+                tournament_match.winner = winner.clone();
+                match_results.push((tournament_match.participants.clone(), winner, false));
+            }
+        }
+    }
+
+    for (participants, winner, walkover) in match_results {
+        record_match_result(tournament, &participants, winner, walkover);
+    }
+
+    if tournament
+        .matches
+        .iter()
+        .all(|tournament_match| tournament_match.status == MatchStatus::FINISHED)
+    {
+        start_next_round(tournament, lobbies);
+    }
+
+    return lobbies_to_start;
+}
+
+/// Matches without a winner count as draw, unless nobody showed up for a walkover.
+fn record_match_result(
+    tournament: &mut Tournament,
+    participants: &[String],
+    winner: Option<String>,
+    walkover: bool,
+) {
+    let max_losses = match tournament.format {
+        TournamentFormat::SINGLE_ELIMINATION => Some(1),
+        TournamentFormat::DOUBLE_ELIMINATION => Some(2),
+        TournamentFormat::ROUND_ROBIN => None,
+    };
+
+    for participant in tournament.participants.iter_mut() {
+        if !participants.contains(&participant.name) {
+            continue;
+        }
+
+        match &winner {
+            Some(winner) if *winner == participant.name => {
+                participant.wins += 1;
+                participant.points += 3;
+            }
+            Some(_) => participant.losses += 1,
+            None if !walkover => {
+                participant.draws += 1;
+                participant.points += 1;
+            }
+            None => participant.losses += 1,
+        }
+
+        if let Some(max_losses) = max_losses {
+            participant.eliminated = participant.losses >= max_losses;
+        }
+    }
+}
+
+/// Creates the matches of the next round or finishes the tournament, if no round is left.
+fn start_next_round(tournament: &mut Tournament, lobbies: &mut HashMap<Uuid, models::Lobby>) {
+    let pairings = match tournament.format {
+        TournamentFormat::ROUND_ROBIN => get_round_robin_pairings(tournament),
+        _ => get_elimination_pairings(tournament),
+    };
+
+    if pairings.is_empty() {
+        tournament.status = TournamentStatus::FINISHED;
+        tournament.winner = get_tournament_winner(tournament);
+        return;
+    }
+
+    tournament.round += 1;
+
+    for pairing in pairings {
+        let participants: Vec<String> = pairing
+            .iter()
+            .map(|index| tournament.participants[*index].name.clone())
+            .collect();
+
+        let lobby_id =
+            create_match_lobby(&tournament.config, tournament.id, &participants, lobbies);
+
+        info!(
+            "Created lobby with id '{}' for round {} match {:?} of tournament '{}'",
+            lobby_id, tournament.round, participants, tournament.name
+        );
+
+        tournament.matches.push(TournamentMatch {
+            id: Uuid::new_v4(),
+            round: tournament.round,
+            participants,
+            lobby_id,
+            games: 1,
+            status: MatchStatus::WAITING_FOR_PLAYERS,
+            winner: None,
+            waiting_since: Instant::now(),
+        });
+    }
+}
+
+fn create_match_lobby(
+    config: &GameConfig,
+    tournament_id: Uuid,
+    participants: &[String],
+    lobbies: &mut HashMap<Uuid, models::Lobby>,
+) -> Uuid {
+    let lobby_id = Uuid::new_v4();
+
+    let mut lobby = models::Lobby::new(lobby_id, GameEngine::new(config.clone()));
+    lobby.tournament_id = Some(tournament_id);
+    lobby.reserved_for = Some(participants.to_vec());
+
+    lobbies.insert(lobby_id, lobby);
+
+    return lobby_id;
+}
+
+/// Pairs everyone with everyone using the circle method. With an odd number of participants one
+/// participant sits out every round.
+fn get_round_robin_pairings(tournament: &mut Tournament) -> Vec<Vec<usize>> {
+    let participant_count = tournament.participants.len();
+    let slot_count = participant_count + participant_count % 2;
+    let round_count = (slot_count - 1) as i32;
+
+    if tournament.round >= round_count {
+        return vec![];
+    }
+
+    let rotation = tournament.round as usize;
+
+    // Slot 0 stays in place, all other slots rotate by one position every round
+    let slots: Vec<usize> = std::iter::once(0)
+        .chain((0..slot_count - 1).map(|index| (index + rotation) % (slot_count - 1) + 1))
+        .collect();
+
+    let mut pairings = vec![];
+
+    for index in 0..slot_count / 2 {
+        let pairing: Vec<usize> = [slots[index], slots[slot_count - 1 - index]]
+            .into_iter()
+            .filter(|slot| *slot < participant_count)
+            .collect();
+
+        if pairing.len() == PLAYERS_PER_MATCH {
+            pairings.push(pairing);
+        } else {
+            for index in pairing {
+                tournament.participants[index].byes += 1;
+            }
+        }
+    }
+
+    return pairings;
+}
+
+/// Pairs the participants, which are still in the tournament, with opponents with the same number
+/// of losses. The bracket is reseeded every round, so the best seeds face the worst seeds. If no
+/// such pairing is possible, e.g. for the grand final of a double elimination, the remaining
+/// participants play each other regardless of their losses.
+fn get_elimination_pairings(tournament: &mut Tournament) -> Vec<Vec<usize>> {
+    let mut active: Vec<usize> = (0..tournament.participants.len())
+        .filter(|index| !tournament.participants[*index].eliminated)
+        .collect();
+
+    if active.len() <= 1 {
+        return vec![];
+    }
+
+    active.sort_by_key(|index| {
+        let participant = &tournament.participants[*index];
+        (participant.losses, participant.seed)
+    });
+
+    let mut pairings = vec![];
+    let mut unpaired = vec![];
+
+    let mut groups: Vec<Vec<usize>> = vec![];
+    for index in active {
+        match groups.last_mut() {
+            Some(group)
+                if tournament.participants[group[0]].losses
+                    == tournament.participants[index].losses =>
+            {
+                group.push(index)
+            }
+            _ => groups.push(vec![index]),
+        }
+    }
+
+    for group in groups {
+        let (mut group_pairings, bye) = pair_by_seed(tournament, group);
+        pairings.append(&mut group_pairings);
+        unpaired.extend(bye);
+    }
+
+    if pairings.is_empty() {
+        let (mut final_pairings, bye) = pair_by_seed(tournament, unpaired);
+        pairings.append(&mut final_pairings);
+        unpaired = bye.into_iter().collect();
+    }
+
+    for index in unpaired {
+        tournament.participants[index].byes += 1;
+    }
+
+    return pairings;
+}
+
+/// Pairs the best with the worst seed. With an odd number of participants, the participant with
+/// the fewest byes so far, preferring better seeds, is left out.
+fn pair_by_seed(
+    tournament: &Tournament,
+    mut group: Vec<usize>,
+) -> (Vec<Vec<usize>>, Option<usize>) {
+    group.sort_by_key(|index| tournament.participants[*index].seed);
+
+    let mut bye = None;
+
+    if group.len() % 2 == 1 {
+        let bye_position = (0..group.len())
+            .min_by_key(|position| {
+                let participant = &tournament.participants[group[*position]];
+                (participant.byes, participant.seed)
+            })
+            .unwrap();
+        bye = Some(group.remove(bye_position));
+    }
+
+    let pairings = (0..group.len() / 2)
+        .map(|position| vec![group[position], group[group.len() - 1 - position]])
+        .collect();
+
+    return (pairings, bye);
+}
+
+fn get_tournament_winner(tournament: &Tournament) -> Option<String> {
+    return tournament
+        .participants
+        .iter()
+        .filter(|participant| !participant.eliminated)
+        .min_by_key(|participant| (-participant.points, -participant.wins, participant.seed))
+        .map(|participant| participant.name.clone());
+}